@@ -13,6 +13,15 @@ use openbrush::traits::Storage;
 /// Implement internal helper trait for Base
 pub trait Internal {
     fn ensure_only_slot(&self, part_id: PartId) -> Result<Part>;
+
+    /// Append the part id to the indexed part ids
+    fn push_part_id(&mut self, part_id: PartId);
+
+    /// Remove the part id from the part ids, moving the last indexed id in its place
+    fn remove_part_id(&mut self, part_id: &PartId);
+
+    /// Get the part id at the index. Ids not migrated yet follow the indexed ones
+    fn part_id_at(&self, index: u32) -> Option<PartId>;
 }
 /// Implement internal helper trait for Base
 impl<T> Internal for T
@@ -29,4 +38,44 @@ where
             return Err(RmrkError::UnknownPartId.into())
         }
     }
+
+    default fn push_part_id(&mut self, part_id: PartId) {
+        let data = self.data::<BaseData>();
+        let index = data.part_count;
+        data.part_index_to_id.insert(index, &part_id);
+        data.part_id_to_index.insert(part_id, &index);
+        data.part_count += 1;
+    }
+
+    default fn remove_part_id(&mut self, part_id: &PartId) {
+        let data = self.data::<BaseData>();
+        let index = match data.part_id_to_index.get(part_id) {
+            Some(index) => index,
+            None => {
+                data.part_ids.retain(|id| id != part_id);
+                return
+            }
+        };
+
+        let last_index = data.part_count - 1;
+        if index != last_index {
+            if let Some(last_id) = data.part_index_to_id.get(last_index) {
+                data.part_index_to_id.insert(index, &last_id);
+                data.part_id_to_index.insert(last_id, &index);
+            }
+        }
+        data.part_index_to_id.remove(last_index);
+        data.part_id_to_index.remove(part_id);
+        data.part_count = last_index;
+    }
+
+    default fn part_id_at(&self, index: u32) -> Option<PartId> {
+        let data = self.data::<BaseData>();
+        if index < data.part_count {
+            return data.part_index_to_id.get(index)
+        }
+        data.part_ids
+            .get((index - data.part_count) as usize)
+            .copied()
+    }
 }
@@ -7,14 +7,22 @@ pub mod internal;
 pub mod traits;
 
 use internal::Internal;
-use traits::Base;
+use traits::{
+    Base,
+    BaseEvents,
+    BaseHooks,
+    BaseRef,
+};
 
 use rmrk_common::{
     errors::{
         Result,
         RmrkError,
     },
-    roles::CONTRIBUTOR,
+    roles::{
+        ADMIN,
+        CONTRIBUTOR,
+    },
     types::*,
 };
 
@@ -40,7 +48,8 @@ pub const STORAGE_BASE_KEY: u32 = openbrush::storage_unique_key!(BaseData);
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_BASE_KEY)]
 pub struct BaseData {
-    /// List of all parts of Base.
+    /// List of all parts of Base, superseded by `part_index_to_id`.
+    /// Only holds ids of contracts deployed before the indexed storage until they are migrated
     pub part_ids: Vec<PartId>,
 
     /// Mapping for all part details.
@@ -51,6 +60,15 @@ pub struct BaseData {
 
     /// Metadata for Base
     pub base_metadata_uri: String,
+
+    /// Number of indexed part ids
+    pub part_count: u32,
+
+    /// Mapping of index to the part id stored at it
+    pub part_index_to_id: Mapping<u32, PartId>,
+
+    /// Mapping of part id to its index
+    pub part_id_to_index: Mapping<PartId, u32>,
 }
 
 impl<T> Base for T
@@ -69,7 +87,7 @@ where
                 return Err(RmrkError::BadConfig.into())
             }
             self.data::<BaseData>().parts.insert(part_id, &part);
-            self.data::<BaseData>().part_ids.push(part_id);
+            self.push_part_id(part_id);
             self.data::<BaseData>().next_part_id += 1;
         }
 
@@ -101,6 +119,62 @@ where
         Ok(())
     }
 
+    /// Remove a single collection address from the equippable addresses of given Part
+    #[modifiers(only_role(CONTRIBUTOR))]
+    default fn remove_equippable_address(
+        &mut self,
+        part_id: PartId,
+        equippable_address: AccountId,
+    ) -> Result<()> {
+        let mut part = self.ensure_only_slot(part_id)?;
+        if !part.equippable.contains(&equippable_address) {
+            return Err(RmrkError::AddressNotEquippable.into())
+        }
+        part.equippable
+            .retain(|address| *address != equippable_address);
+        self.data::<BaseData>().parts.insert(part_id, &part);
+        self._emit_equippable_address_removed_event(part_id, equippable_address);
+
+        Ok(())
+    }
+
+    /// Replace the details of an existing Part
+    #[modifiers(only_role(CONTRIBUTOR))]
+    default fn update_part(&mut self, part_id: PartId, part: Part) -> Result<()> {
+        let current_part = self
+            .data::<BaseData>()
+            .parts
+            .get(part_id)
+            .ok_or(RmrkError::UnknownPartId)?;
+        if part.part_type != current_part.part_type {
+            self._ensure_part_type_changeable(part_id)?;
+        }
+        if part.part_type == PartType::Fixed
+            && (!part.equippable.is_empty() || part.is_equippable_by_all)
+        {
+            return Err(RmrkError::BadConfig.into())
+        }
+        self.data::<BaseData>().parts.insert(part_id, &part);
+        self._emit_part_updated_event(part_id);
+
+        Ok(())
+    }
+
+    /// Remove a Part from the base
+    #[modifiers(only_role(CONTRIBUTOR))]
+    default fn remove_part(&mut self, part_id: PartId) -> Result<()> {
+        self.data::<BaseData>()
+            .parts
+            .get(part_id)
+            .ok_or(RmrkError::UnknownPartId)?;
+        self._ensure_part_removable(part_id)?;
+        self.data::<BaseData>().parts.remove(part_id);
+        self.remove_part_id(&part_id);
+        self._emit_part_removed_event(part_id);
+
+        Ok(())
+    }
+
     /// Sets the is_equippable_by_all flag to true, meaning that any collection may be equipped into the `PartId`
     #[modifiers(only_role(CONTRIBUTOR))]
     default fn set_equippable_by_all(&mut self, part_id: PartId) -> Result<()> {
@@ -129,7 +203,28 @@ where
 
     /// Get the number of parts.
     default fn get_parts_count(&self) -> PartId {
-        self.data::<BaseData>().next_part_id
+        let data = self.data::<BaseData>();
+        data.part_count + data.part_ids.len() as u32
+    }
+
+    /// Get a page of the part ids.
+    default fn get_part_ids(&self, offset: u32, limit: u32) -> Vec<PartId> {
+        let end = offset.saturating_add(limit).min(self.get_parts_count());
+        (offset..end)
+            .filter_map(|index| self.part_id_at(index))
+            .collect()
+    }
+
+    /// Move part ids of the legacy list to the indexed storage.
+    #[modifiers(only_role(ADMIN))]
+    default fn migrate_part_ids(&mut self, limit: u32) -> Result<u32> {
+        let data = self.data::<BaseData>();
+        let count = (limit as usize).min(data.part_ids.len());
+        let migrated: Vec<PartId> = data.part_ids.drain(..count).collect();
+        for part_id in migrated {
+            self.push_part_id(part_id);
+        }
+        Ok(self.data::<BaseData>().part_ids.len() as u32)
     }
 
     /// Get the part details for the given PartId.
@@ -137,12 +232,11 @@ where
         self.data::<BaseData>().parts.get(part_id)
     }
 
-    /// Check whether the given address is allowed to equip the desired `PartId`.
-    default fn ensure_equippable(&self, part_id: PartId, target_address: AccountId) -> Result<()> {
-        if let Some(part) = self.data::<BaseData>().parts.get(part_id) {
-            if !part.equippable.contains(&target_address) {
-                return Err(RmrkError::AddressNotEquippable.into())
-            }
+    /// Check whether the collection is allowed to equip into the slot `PartId`.
+    default fn check_slot_equippable(&self, part_id: PartId, collection: AccountId) -> Result<()> {
+        let part = self.ensure_only_slot(part_id)?;
+        if !part.is_equippable_by_all && !part.equippable.contains(&collection) {
+            return Err(RmrkError::AddressNotEquippable.into())
         }
 
         Ok(())
@@ -156,4 +250,62 @@ where
 
         return false
     }
+
+    /// Check that every `PartId` exists in the local or referenced base
+    default fn ensure_parts_exist(
+        &self,
+        base_address: &Option<AccountId>,
+        part_ids: &Vec<PartId>,
+    ) -> Result<()> {
+        let this_contract = Self::env().account_id();
+        for part_id in part_ids {
+            let exists = match base_address {
+                Some(base) if *base != this_contract => {
+                    BaseRef::get_part_builder(base, *part_id)
+                        .fire()
+                        .unwrap_or(None)
+                        .is_some()
+                }
+                _ => self.data::<BaseData>().parts.get(part_id).is_some(),
+            };
+            if !exists {
+                return Err(RmrkError::UnknownPartId.into())
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> BaseHooks for T
+where
+    T: Storage<BaseData>,
+{
+    /// Without a multiasset module, no asset entry of this contract references the Part
+    default fn _ensure_part_removable(&self, _part_id: PartId) -> Result<()> {
+        Ok(())
+    }
+
+    /// Without multiasset and equippable modules, nothing references or is equipped into the Part
+    default fn _ensure_part_type_changeable(&self, _part_id: PartId) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<T> BaseEvents for T
+where
+    T: Storage<BaseData>,
+{
+    /// Used to notify listeners that the details of a Part are updated.
+    default fn _emit_part_updated_event(&self, _part_id: PartId) {}
+
+    /// Used to notify listeners that a Part is removed from the base.
+    default fn _emit_part_removed_event(&self, _part_id: PartId) {}
+
+    /// Used to notify listeners that a collection can no longer be equipped into a Slot.
+    default fn _emit_equippable_address_removed_event(
+        &self,
+        _part_id: PartId,
+        _equippable_address: AccountId,
+    ) {
+    }
 }
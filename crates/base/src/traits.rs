@@ -36,6 +36,32 @@ pub trait Base {
     #[ink(message)]
     fn reset_equippable_addresses(&mut self, part_id: PartId) -> Result<()>;
 
+    /// Remove a single collection address from the equippable addresses of given Part.
+    /// Fails with `AddressNotEquippable` if the address isn't listed.
+    /// Emits an {EquippableAddressRemoved} event.
+    #[ink(message)]
+    fn remove_equippable_address(
+        &mut self,
+        part_id: PartId,
+        equippable_address: AccountId,
+    ) -> Result<()>;
+
+    /// Replace the details of an existing Part, e.g. to correct its `z`, metadata or type.
+    /// Fixed parts can't list equippable addresses or be equippable by all.
+    /// Fails with `PartInUse` when changing the type of a Part referenced by an asset entry of
+    /// this contract or with a child equipped into it.
+    /// Emits a {PartUpdated} event.
+    #[ink(message)]
+    fn update_part(&mut self, part_id: PartId, part: Part) -> Result<()>;
+
+    /// Remove a Part from the base. Its `PartId` is not reused.
+    /// Fails with `PartInUse` if an asset entry of this contract references the Part, or a child
+    /// is equipped into it. Asset entries of other collections referencing this base by address
+    /// are not checked.
+    /// Emits a {PartRemoved} event.
+    #[ink(message)]
+    fn remove_part(&mut self, part_id: PartId) -> Result<()>;
+
     /// Sets the is_equippable_by_all flag to true, meaning that any collection may be equipped into the `PartId`
     #[ink(message)]
     fn set_equippable_by_all(&mut self, part_id: PartId) -> Result<()>;
@@ -48,19 +74,82 @@ pub trait Base {
     #[ink(message)]
     fn get_base_metadata(&self) -> PreludeString;
 
-    /// Get the list of all parts.
+    /// Get the number of parts.
     #[ink(message)]
     fn get_parts_count(&self) -> PartId;
 
+    /// Get a page of the part ids.
+    /// # Arguments
+    ///  * `offset` Number of part ids to skip
+    ///  * `limit` Maximum number of part ids to return
+    #[ink(message)]
+    fn get_part_ids(&self, offset: u32, limit: u32) -> Vec<PartId>;
+
+    /// Move part ids of contracts deployed with the unindexed part id list to the indexed
+    /// storage. Call repeatedly until it returns 0. Requires `ADMIN` role.
+    /// Returns the number of part ids left to migrate.
+    #[ink(message)]
+    fn migrate_part_ids(&mut self, limit: u32) -> Result<u32>;
+
     /// Get the part details for the given PartId.
     #[ink(message)]
     fn get_part(&self, part_id: PartId) -> Option<Part>;
 
-    /// Check whether the given address is allowed to equip the desired `PartId`.
+    /// Check whether the collection is allowed to equip into the slot `PartId`.
+    /// # Requirements:
+    ///  * `partId` must exist, otherwise fails with `UnknownPartId`
+    ///  * `partId` must be a Slot, otherwise fails with `PartIsNotSlot`
+    ///  * The slot must be equippable by all or list the collection, otherwise fails with
+    ///    `AddressNotEquippable`
     #[ink(message)]
-    fn ensure_equippable(&self, part_id: PartId, target_address: AccountId) -> Result<()>;
+    fn check_slot_equippable(&self, part_id: PartId, collection: AccountId) -> Result<()>;
 
     /// Checks if the given `PartId` can be equipped by any collection
     #[ink(message)]
     fn is_equippable_by_all(&self, part_id: PartId) -> bool;
+
+    /// Check that every `PartId` exists in the base.
+    /// `None` or this contract's address checks the local base, any other address is queried by
+    /// a cross contract call.
+    fn ensure_parts_exist(
+        &self,
+        base_address: &Option<AccountId>,
+        part_ids: &Vec<PartId>,
+    ) -> Result<()>;
+}
+
+/// Trait definitions for Base hooks
+#[openbrush::trait_definition]
+pub trait BaseHooks {
+    /// Called before a Part is removed.
+    /// Fails if the Part is still referenced by an asset entry or has something equipped.
+    fn _ensure_part_removable(&self, part_id: PartId) -> Result<()>;
+
+    /// Called before the type of a Part is changed.
+    /// Fails if the Part is still referenced by an asset entry or has something equipped.
+    fn _ensure_part_type_changeable(&self, part_id: PartId) -> Result<()>;
+}
+
+/// Trait definitions for Base ink events
+#[openbrush::trait_definition]
+pub trait BaseEvents {
+    /// Used to notify listeners that the details of a Part are updated.
+    /// # Arguments
+    /// * partId ID of the updated Part
+    fn _emit_part_updated_event(&self, part_id: PartId);
+
+    /// Used to notify listeners that a Part is removed from the base.
+    /// # Arguments
+    /// * partId ID of the removed Part
+    fn _emit_part_removed_event(&self, part_id: PartId);
+
+    /// Used to notify listeners that a collection can no longer be equipped into a Slot.
+    /// # Arguments
+    /// * partId ID of the Slot
+    /// * equippableAddress Address of the removed collection
+    fn _emit_equippable_address_removed_event(
+        &self,
+        part_id: PartId,
+        equippable_address: AccountId,
+    );
 }
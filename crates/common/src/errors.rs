@@ -77,24 +77,37 @@ pub enum RmrkError {
     AddressNotEquippable,
     AlreadyAddedAsset,
     AlreadyAddedChild,
+    AssetHasNoBase,
     AssetHasNoParts,
     AssetIdAlreadyExists,
     AssetIdNotFound,
+    AssetIdsExhausted,
+    AssetInUse,
+    AssetIsDeprecated,
     BadConfig,
     BadMintValue,
     BadPriorityLength,
     CannotMintZeroTokens,
+    ChildIsEquipped,
+    ChildIsNested,
     ChildNotFound,
     UriNotFound,
     CollectionIsFull,
     InvalidAssetId,
     InvalidParentId,
     InvalidTokenId,
+    MaxAcceptedChildrenReached,
+    MaxPendingAssetsReached,
+    MaxPendingChildrenReached,
+    NestingCycle,
+    NestingTooDeep,
     NotEquipped,
     NotTokenOwner,
+    PartInUse,
     PartIsNotSlot,
     SlotAlreayUsed,
     TargetAssetCannotReceiveSlot,
+    UnexpectedNumberOfAssets,
     UnknownEquippableAsset,
     UnknownPart,
     UnknownPartId,
@@ -110,24 +123,37 @@ impl ToString for RmrkError {
             RmrkError::AddressNotEquippable => String::from("AddressNotEquippable"),
             RmrkError::AlreadyAddedAsset => String::from("AlreadyAddedAsset"),
             RmrkError::AlreadyAddedChild => String::from("AlreadyAddedChild"),
+            RmrkError::AssetHasNoBase => String::from("AssetHasNoBase"),
             RmrkError::AssetHasNoParts => String::from("AssetHasNoParts"),
             RmrkError::AssetIdAlreadyExists => String::from("AssetIdAlreadyExists"),
             RmrkError::AssetIdNotFound => String::from("AssetIdNotFound"),
+            RmrkError::AssetIdsExhausted => String::from("AssetIdsExhausted"),
+            RmrkError::AssetInUse => String::from("AssetInUse"),
+            RmrkError::AssetIsDeprecated => String::from("AssetIsDeprecated"),
             RmrkError::BadConfig => String::from("BadConfig"),
             RmrkError::BadMintValue => String::from("BadMintValue"),
             RmrkError::BadPriorityLength => String::from("BadPriorityLength"),
             RmrkError::CannotMintZeroTokens => String::from("CannotMintZeroTokens"),
+            RmrkError::ChildIsEquipped => String::from("ChildIsEquipped"),
+            RmrkError::ChildIsNested => String::from("ChildIsNested"),
             RmrkError::ChildNotFound => String::from("ChildNotFound"),
             RmrkError::UriNotFound => String::from("UriNotFound"),
             RmrkError::CollectionIsFull => String::from("CollectionIsFull"),
             RmrkError::InvalidAssetId => String::from("InvalidAssetId"),
             RmrkError::InvalidParentId => String::from("InvalidParentId"),
             RmrkError::InvalidTokenId => String::from("InvalidTokenId"),
+            RmrkError::MaxAcceptedChildrenReached => String::from("MaxAcceptedChildrenReached"),
+            RmrkError::MaxPendingAssetsReached => String::from("MaxPendingAssetsReached"),
+            RmrkError::MaxPendingChildrenReached => String::from("MaxPendingChildrenReached"),
+            RmrkError::NestingCycle => String::from("NestingCycle"),
+            RmrkError::NestingTooDeep => String::from("NestingTooDeep"),
             RmrkError::NotEquipped => String::from("NotEquipped"),
             RmrkError::NotTokenOwner => String::from("NotTokenOwner"),
+            RmrkError::PartInUse => String::from("PartInUse"),
             RmrkError::PartIsNotSlot => String::from("PartIsNotSlot"),
             RmrkError::SlotAlreayUsed => String::from("SlotAlreayUsed"),
             RmrkError::TargetAssetCannotReceiveSlot => String::from("TargetAssetCannotReceiveSlot"),
+            RmrkError::UnexpectedNumberOfAssets => String::from("UnexpectedNumberOfAssets"),
             RmrkError::UnknownEquippableAsset => String::from("UnknownEquippableAsset"),
             RmrkError::UnknownPart => String::from("UnknownPart"),
             RmrkError::UnknownPartId => String::from("UnknownPartId"),
@@ -26,7 +26,7 @@ pub type AssetId = u32;
 pub type EquippableGroupId = u32;
 
 /// Part's details
-#[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Default, Debug)]
+#[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Default, Debug, PartialEq)]
 #[cfg_attr(
     feature = "std",
     derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
@@ -77,6 +77,36 @@ pub enum PartType {
     Fixed,
 }
 
+/// Used to define the media type of an asset. Renderers use it to pick the assets they can display.
+#[derive(
+    scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, Clone, Copy, PartialEq,
+)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+)]
+pub enum AssetMediaType {
+    Image,
+    Audio,
+    Video,
+    Model,
+    Composable,
+}
+
+/// Typed asset details, stored per asset entry
+#[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink_storage::traits::StorageLayout)
+)]
+pub struct TypedAsset {
+    /// Media type of the asset
+    pub media_type: AssetMediaType,
+
+    /// Uri of a preview image for the asset
+    pub thumbnail_uri: Option<String>,
+}
+
 /// Used to define Equipment
 #[derive(scale::Encode, scale::Decode, SpreadLayout, PackedLayout, Debug, Clone, PartialEq)]
 #[cfg_attr(
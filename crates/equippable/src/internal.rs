@@ -1,4 +1,10 @@
-use rmrk_base::BaseData;
+use rmrk_base::{
+    traits::{
+        Base,
+        BaseRef,
+    },
+    BaseData,
+};
 
 use rmrk_common::{
     errors::{
@@ -53,6 +59,21 @@ pub trait Internal {
 
     /// Used to ensure a token is equipped and can be un-equipped.
     fn ensure_equipped(&self, token_id: &Id, slot_part_id: &PartId) -> Result<Equipment>;
+
+    /// Record the slot the child is equipped into.
+    fn add_equipped_slot(&mut self, token_id: &Id, child_nft: &ChildNft, slot_part_id: PartId);
+
+    /// Remove the slot from the slots the child is equipped into.
+    fn remove_equipped_slot(&mut self, token_id: &Id, child_nft: &ChildNft, slot_part_id: &PartId);
+
+    /// Check in the asset's base whether the child collection can be equipped into the slot.
+    /// Assets with a base address of another contract are checked by a cross contract call.
+    fn ensure_slot_equippable(
+        &self,
+        asset_id: &AssetId,
+        slot_part_id: &PartId,
+        child_address: &AccountId,
+    ) -> Result<()>;
 }
 
 /// Implement internal helper trait for Equippable
@@ -64,6 +85,7 @@ where
         + MultiAsset
         + MultiAssetInternal
         + Storage<BaseData>
+        + Base
         + Utils,
 {
     /// Check if slot is already used/equipped.
@@ -136,4 +158,71 @@ where
             return Err(RmrkError::NotEquipped.into())
         }
     }
+
+    /// Check in the asset's base whether the child collection can be equipped into the slot.
+    default fn ensure_slot_equippable(
+        &self,
+        asset_id: &AssetId,
+        slot_part_id: &PartId,
+        child_address: &AccountId,
+    ) -> Result<()> {
+        let this_contract = Self::env().account_id();
+        match self.data::<MultiAssetData>().asset_bases.get(asset_id) {
+            Some(base) if base != this_contract => {
+                BaseRef::check_slot_equippable_builder(&base, *slot_part_id, *child_address)
+                    .fire()
+                    .unwrap_or(Err(RmrkError::AddressNotEquippable.into()))
+            }
+            _ => self.check_slot_equippable(*slot_part_id, *child_address),
+        }
+    }
+
+    /// Record the slot the child is equipped into
+    default fn add_equipped_slot(
+        &mut self,
+        token_id: &Id,
+        child_nft: &ChildNft,
+        slot_part_id: PartId,
+    ) {
+        let data = self.data::<EquippableData>();
+        let mut slot_part_ids = data
+            .equipped_children
+            .get((token_id, child_nft))
+            .unwrap_or_default();
+        if !slot_part_ids.contains(&slot_part_id) {
+            slot_part_ids.push(slot_part_id);
+            data.equipped_children
+                .insert((token_id, child_nft), &slot_part_ids);
+            let equipments = data.slot_equipments.get(slot_part_id).unwrap_or(0);
+            data.slot_equipments
+                .insert(slot_part_id, &equipments.saturating_add(1));
+        }
+    }
+
+    /// Remove the slot from the slots the child is equipped into
+    default fn remove_equipped_slot(
+        &mut self,
+        token_id: &Id,
+        child_nft: &ChildNft,
+        slot_part_id: &PartId,
+    ) {
+        let data = self.data::<EquippableData>();
+        let mut slot_part_ids = data
+            .equipped_children
+            .get((token_id, child_nft))
+            .unwrap_or_default();
+        if !slot_part_ids.contains(slot_part_id) {
+            return
+        }
+        slot_part_ids.retain(|part_id| part_id != slot_part_id);
+        let equipments = data.slot_equipments.get(slot_part_id).unwrap_or(0);
+        data.slot_equipments
+            .insert(slot_part_id, &equipments.saturating_sub(1));
+        if slot_part_ids.is_empty() {
+            data.equipped_children.remove((token_id, child_nft));
+        } else {
+            data.equipped_children
+                .insert((token_id, child_nft), &slot_part_ids);
+        }
+    }
 }
@@ -7,16 +7,14 @@ pub mod internal;
 pub mod traits;
 
 use internal::Internal;
-use rmrk_base::{
-    traits::Base,
-    BaseData,
-};
+use rmrk_base::BaseData;
 
 use rmrk_common::{
     errors::{
         Result,
         RmrkError,
     },
+    roles::ADMIN,
     types::*,
     utils::Utils,
 };
@@ -32,13 +30,15 @@ use traits::{
     EquippableEvents,
 };
 
+use ink_prelude::vec::Vec;
 use ink_storage::Mapping;
 
 use openbrush::{
     contracts::{
-        access_control,
+        access_control::*,
         psp34::extensions::enumerable::*,
     },
+    modifiers,
     traits::{
         AccountId,
         Storage,
@@ -53,6 +53,11 @@ pub const STORAGE_EQUIPMENT_KEY: u32 = openbrush::storage_unique_key!(EquipmentD
 pub struct EquippableData {
     pub equipment: Mapping<(Id, PartId), Equipment>,
     pub valid_parent_slot: Mapping<(EquippableGroupId, AccountId), PartId>,
+    /// Slots into which each child is equipped on its parent token.
+    /// Equipment from before this index was added is recorded by `migrate_equipment`.
+    pub equipped_children: Mapping<(Id, ChildNft), Vec<PartId>>,
+    /// Number of children equipped into each slot, across all tokens.
+    pub slot_equipments: Mapping<PartId, u32>,
 }
 
 impl<T> Equippable for T
@@ -86,8 +91,8 @@ where
         //     child_asset_id,
         //     slot_part_id)?;
 
-        // Check from base perspective
-        self.ensure_equippable(slot_part_id, child_nft.0)?;
+        // Check from base perspective, the asset's base may be a separate catalog contract
+        self.ensure_slot_equippable(&asset_id, &slot_part_id, &child_nft.0)?;
 
         // insert equipment
         let equipment = Equipment {
@@ -98,6 +103,7 @@ where
         self.data::<EquippableData>()
             .equipment
             .insert((token_id.clone(), slot_part_id), &equipment);
+        self.add_equipped_slot(&token_id, &child_nft, slot_part_id);
 
         self.emit_child_asset_equipped(token_id, asset_id, slot_part_id, child_nft, child_asset_id);
         Ok(())
@@ -112,6 +118,7 @@ where
         self.data::<EquippableData>()
             .equipment
             .remove((token_id.clone(), slot_part_id));
+        self.remove_equipped_slot(&token_id, &equipment.child_nft, &slot_part_id);
 
         self.emit_child_asset_unequipped(token_id, equipment.asset_id, slot_part_id);
         Ok(())
@@ -158,6 +165,76 @@ where
             return Err(RmrkError::AssetIdNotFound.into())
         }
     }
+
+    /// Used to ensure the child is not equipped into any slot of the token.
+    default fn ensure_child_not_equipped(&self, token_id: &Id, child_nft: &ChildNft) -> Result<()> {
+        if self
+            .data::<EquippableData>()
+            .equipped_children
+            .get((token_id, child_nft))
+            .is_some()
+        {
+            return Err(RmrkError::ChildIsEquipped.into())
+        }
+        Ok(())
+    }
+
+    /// Used to ensure no child is equipped into the slot.
+    default fn ensure_slot_unequipped(&self, slot_part_id: PartId) -> Result<()> {
+        if self
+            .data::<EquippableData>()
+            .slot_equipments
+            .get(slot_part_id)
+            .unwrap_or(0)
+            > 0
+        {
+            return Err(RmrkError::PartInUse.into())
+        }
+        Ok(())
+    }
+
+    /// Used to unequip the child from every slot of the token it is equipped into.
+    default fn unequip_child(&mut self, token_id: &Id, child_nft: &ChildNft) -> Result<()> {
+        let slot_part_ids = self
+            .data::<EquippableData>()
+            .equipped_children
+            .get((token_id, child_nft))
+            .unwrap_or_default();
+        for slot_part_id in slot_part_ids {
+            let equipment = self.ensure_equipped(token_id, &slot_part_id)?;
+            let data = self.data::<EquippableData>();
+            data.equipment.remove((token_id, slot_part_id));
+            let equipments = data.slot_equipments.get(slot_part_id).unwrap_or(0);
+            data.slot_equipments
+                .insert(slot_part_id, &equipments.saturating_sub(1));
+
+            self.emit_child_asset_unequipped(token_id.clone(), equipment.asset_id, slot_part_id);
+        }
+        self.data::<EquippableData>()
+            .equipped_children
+            .remove((token_id, child_nft));
+        Ok(())
+    }
+
+    /// Record children equipped into the given slots of the token before equipped children
+    /// were indexed
+    #[modifiers(only_role(ADMIN))]
+    default fn migrate_equipment(
+        &mut self,
+        token_id: Id,
+        slot_part_ids: Vec<PartId>,
+    ) -> Result<()> {
+        for slot_part_id in slot_part_ids {
+            if let Some(equipment) = self
+                .data::<EquippableData>()
+                .equipment
+                .get((&token_id, slot_part_id))
+            {
+                self.add_equipped_slot(&token_id, &equipment.child_nft, slot_part_id);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<T> EquippableEvents for T {
@@ -1,13 +1,13 @@
 //! Trait definitions for Equippable module
-use rmrk_common::{
-    errors::Result,
-    types::*,
-};
-// use ink_prelude::vec::Vec;
+use ink_prelude::vec::Vec;
 use openbrush::{
     contracts::psp34::Id,
     traits::AccountId,
 };
+use rmrk_common::{
+    errors::Result,
+    types::*,
+};
 
 #[openbrush::wrapper]
 pub type EquippableRef = dyn Equippable;
@@ -102,6 +102,32 @@ pub trait Equippable {
     ///    * EquippableAsset
     #[ink(message)]
     fn get_asset_and_equippable_data(&self, token_id: Id, asset_id: AssetId) -> Result<Asset>;
+
+    /// Used to ensure the child is not equipped into any slot of the token.
+    /// Called before a child leaves its parent token.
+    /// Equipment made before equipped children were indexed is only detected once
+    /// `migrate_equipment` has recorded it.
+    fn ensure_child_not_equipped(&self, token_id: &Id, child_nft: &ChildNft) -> Result<()>;
+
+    /// Used to ensure no child is equipped into the slot, in any token.
+    /// Slots of other bases with the same `PartId` are counted as well.
+    /// Return `PartInUse` error if one is.
+    fn ensure_slot_unequipped(&self, slot_part_id: PartId) -> Result<()>;
+
+    /// Used to unequip the child from every slot of the token it is equipped into.
+    /// Emits an {ChildAssetUnequipped} event for each slot.
+    fn unequip_child(&mut self, token_id: &Id, child_nft: &ChildNft) -> Result<()>;
+
+    /// Used to record children equipped before equipped children were indexed, so they can't
+    /// leave their parent token while equipped.
+    /// Needs to be called for every token with equipment after upgrading a deployed contract.
+    /// # Requirements
+    ///  * Caller must have `ADMIN` role
+    /// # Arguments:
+    ///  * `token_id` ID of the token with equipment
+    ///  * `slot_part_ids` IDs of the slots to check for equipped children
+    #[ink(message)]
+    fn migrate_equipment(&mut self, token_id: Id, slot_part_ids: Vec<PartId>) -> Result<()>;
 }
 
 /// Trait definitions for Resource ink events
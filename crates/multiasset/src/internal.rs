@@ -1,6 +1,7 @@
 use crate::{
     traits::MultiAssetEvents,
     MultiAssetData,
+    DEFAULT_MAX_PENDING_ASSETS,
 };
 
 use rmrk_common::{
@@ -17,7 +18,9 @@ use ink_prelude::vec::Vec;
 use openbrush::{
     contracts::psp34::extensions::enumerable::*,
     traits::{
+        AccountId,
         Storage,
+        String,
     },
 };
 
@@ -39,9 +42,50 @@ pub trait Internal {
     fn add_to_accepted_assets(&mut self, token_id: &Id, asset_id: &AssetId);
 
     /// Add the asset to the list of pending assets
-    fn add_to_pending_assets(&mut self, token_id: &Id, asset_id: &AssetId);
+    fn add_to_pending_assets(&mut self, token_id: &Id, asset_id: &AssetId) -> Result<()>;
 
-    /// Replace asset by another AssetId
+    /// Get the effective maximum number of pending assets per token
+    fn max_pending_assets(&self) -> u32;
+
+    /// Get the priorities of the token's accepted assets.
+    /// Tokens with assets accepted before priorities were stored get their positions as priorities
+    fn asset_priorities(&self, token_id: &Id) -> Vec<u16>;
+
+    /// Check if the account is approved to manage the token's assets, for this token or for all
+    /// assets of the token owner
+    fn is_asset_operator(
+        &self,
+        token_owner: &AccountId,
+        token_id: &Id,
+        operator: &AccountId,
+    ) -> bool;
+
+    /// Check if the asset is pending or accepted on the token. Return error if it isn't
+    fn ensure_asset_on_token(&self, token_id: &Id, asset_id: &AssetId) -> Result<()>;
+
+    /// Check if caller is the token owner or is approved to manage the token's assets.
+    /// Return the token owner
+    fn ensure_asset_manager(&self, token_id: &Id) -> Result<AccountId>;
+
+    /// Check that the asset exists and isn't deprecated
+    fn ensure_asset_addable(&self, asset_id: AssetId) -> Result<()>;
+
+    /// Count one more token using the asset
+    fn increase_asset_usage(&mut self, asset_id: &AssetId);
+
+    /// Count one less token using the asset
+    fn decrease_asset_usage(&mut self, asset_id: &AssetId);
+
+    /// Count one more asset entry referencing each part, if the parts are in the local base
+    fn increase_part_references(&mut self, base_address: &Option<AccountId>, part_ids: &[PartId]);
+
+    /// Count one less asset entry referencing each part, if the parts are in the local base
+    fn decrease_part_references(&mut self, base_address: &Option<AccountId>, part_ids: &[PartId]);
+
+    /// Check that the asset can be proposed as a replacement. Return error if it isn't accepted
+    fn ensure_replaceable(&self, token_id: &Id, replaces_id: &AssetId) -> Result<()>;
+
+    /// Replace an accepted asset by another AssetId, keeping its position and priority
     fn replace_asset(
         &mut self,
         token_id: &Id,
@@ -54,6 +98,25 @@ pub trait Internal {
 
     /// Remove the asset to the list of accepted assets
     fn remove_from_accepted_assets(&mut self, token_id: &Id, asset_id: &AssetId) -> Result<()>;
+
+    /// Append the asset id to the indexed collection asset ids
+    fn push_collection_asset_id(&mut self, asset_id: AssetId);
+
+    /// Remove the asset id from the collection asset ids, moving the last indexed id in its place
+    fn remove_collection_asset_id(&mut self, asset_id: &AssetId);
+
+    /// Get the collection asset id at the index. Ids not migrated yet follow the indexed ones
+    fn collection_asset_id_at(&self, index: u32) -> Option<AssetId>;
+
+    /// Store a new asset entry and move `next_asset_id` past its id
+    fn insert_asset_entry(
+        &mut self,
+        asset_id: AssetId,
+        equippable_group_id: EquippableGroupId,
+        base_address: Option<AccountId>,
+        asset_uri: String,
+        part_ids: Vec<PartId>,
+    );
 }
 
 /// Implement internal helper trait for MultiAsset
@@ -101,35 +164,110 @@ where
         Ok(())
     }
 
-    /// Add the asset to the list of accepted assets
+    /// Add the asset to the list of accepted assets, replacing the proposed asset if it's still accepted
     default fn add_to_accepted_assets(&mut self, token_id: &Id, asset_id: &AssetId) {
+        let data = self.data::<MultiAssetData>();
+        let replaces_id = data.asset_replacements.get((token_id, asset_id));
+        data.asset_replacements.remove((token_id, asset_id));
+
+        if let Some(replace_with_id) = replaces_id {
+            if self
+                .replace_asset(token_id, asset_id, &replace_with_id)
+                .is_ok()
+            {
+                self._emit_asset_accepted_event(token_id, asset_id, &replaces_id);
+                return
+            }
+        }
+
         let mut assets = self
             .data::<MultiAssetData>()
             .accepted_assets
             .get(&token_id)
             .unwrap_or(Vec::new());
         if !assets.contains(&asset_id) {
+            let mut priorities = self.asset_priorities(token_id);
+            priorities.push(assets.len() as u16);
             assets.push(*asset_id);
-            self.data::<MultiAssetData>()
-                .accepted_assets
-                .insert(&token_id, &assets);
+            let data = self.data::<MultiAssetData>();
+            data.accepted_assets.insert(&token_id, &assets);
+            data.asset_priorities.insert(&token_id, &priorities);
         }
-        self._emit_asset_accepted_event(token_id, asset_id);
+        self._emit_asset_accepted_event(token_id, asset_id, &None);
     }
 
     /// Add the asset to the list of pending assets
-    default fn add_to_pending_assets(&mut self, token_id: &Id, asset_id: &AssetId) {
+    default fn add_to_pending_assets(&mut self, token_id: &Id, asset_id: &AssetId) -> Result<()> {
+        let max_pending = self.max_pending_assets();
         let mut assets = self
             .data::<MultiAssetData>()
             .pending_assets
             .get(&token_id)
             .unwrap_or(Vec::new());
         if !assets.contains(&asset_id) {
+            if assets.len() >= max_pending as usize {
+                return Err(RmrkError::MaxPendingAssetsReached.into())
+            }
             assets.push(*asset_id);
             self.data::<MultiAssetData>()
                 .pending_assets
                 .insert(&token_id, &assets);
         }
+        Ok(())
+    }
+
+    /// Check if caller is the token owner or is approved to manage the token's assets
+    default fn ensure_asset_manager(&self, token_id: &Id) -> Result<AccountId> {
+        let token_owner = self.ensure_exists_and_get_owner(token_id)?;
+        let caller = Self::env().caller();
+        if caller == token_owner {
+            return Ok(token_owner)
+        }
+        if self.is_asset_operator(&token_owner, token_id, &caller) {
+            return Ok(token_owner)
+        }
+        Err(RmrkError::NotTokenOwner.into())
+    }
+
+    /// Check if the account is approved to manage the token's assets
+    default fn is_asset_operator(
+        &self,
+        token_owner: &AccountId,
+        token_id: &Id,
+        operator: &AccountId,
+    ) -> bool {
+        let data = self.data::<MultiAssetData>();
+        data.asset_operators.get((token_owner, operator)).is_some()
+            || data.asset_approvals.get((token_owner, token_id)) == Some(*operator)
+    }
+
+    /// Check if the asset is pending or accepted on the token
+    default fn ensure_asset_on_token(&self, token_id: &Id, asset_id: &AssetId) -> Result<()> {
+        let data = self.data::<MultiAssetData>();
+        let accepted = data.accepted_assets.get(token_id).unwrap_or_default();
+        let pending = data.pending_assets.get(token_id).unwrap_or_default();
+        if !accepted.contains(asset_id) && !pending.contains(asset_id) {
+            return Err(RmrkError::AssetIdNotFound.into())
+        }
+        Ok(())
+    }
+
+    /// Get the priorities of the token's accepted assets
+    default fn asset_priorities(&self, token_id: &Id) -> Vec<u16> {
+        let data = self.data::<MultiAssetData>();
+        let accepted = data.accepted_assets.get(token_id).unwrap_or_default().len();
+        match data.asset_priorities.get(token_id) {
+            Some(priorities) if priorities.len() == accepted => priorities,
+            _ => (0..accepted as u16).collect(),
+        }
+    }
+
+    /// Get the effective maximum number of pending assets per token
+    default fn max_pending_assets(&self) -> u32 {
+        match self.data::<MultiAssetData>().max_pending_assets {
+            0 => DEFAULT_MAX_PENDING_ASSETS,
+            limit => limit,
+        }
     }
 
     /// remove the asset from the list of pending assets
@@ -173,18 +311,164 @@ where
             .iter()
             .position(|a| a == asset_id)
             .ok_or(RmrkError::InvalidTokenId)?;
+        let mut priorities = self.asset_priorities(token_id);
         assets.remove(index);
+        priorities.remove(index);
 
-        self.data::<MultiAssetData>()
-            .accepted_assets
-            .insert(&token_id, &assets);
+        let data = self.data::<MultiAssetData>();
+        data.accepted_assets.insert(&token_id, &assets);
+        data.asset_priorities.insert(&token_id, &priorities);
+        data.token_asset_uris.remove((token_id, asset_id));
 
         Ok(())
     }
 
-    // TODO:
-    // * add replace pending storage ( ie collection issuer might suggest asset replace on a token with different owner )
-    // * add "upsert" operation ( if replace failed add as a new asset )
+    /// Store a new asset entry and move `next_asset_id` past its id
+    default fn insert_asset_entry(
+        &mut self,
+        asset_id: AssetId,
+        equippable_group_id: EquippableGroupId,
+        base_address: Option<AccountId>,
+        asset_uri: String,
+        part_ids: Vec<PartId>,
+    ) {
+        self.increase_part_references(&base_address, &part_ids);
+        let data = self.data::<MultiAssetData>();
+        if let Some(base_address) = base_address {
+            data.asset_bases.insert(asset_id, &base_address);
+        }
+        data.collection_asset_entries.insert(
+            asset_id,
+            &Asset {
+                equippable_group_id,
+                asset_uri,
+                part_ids,
+            },
+        );
+        if asset_id >= data.next_asset_id {
+            data.next_asset_id = asset_id.saturating_add(1);
+        }
+        self.push_collection_asset_id(asset_id);
+        self._emit_asset_set_event(&asset_id);
+    }
+
+    /// Append the asset id to the indexed collection asset ids
+    default fn push_collection_asset_id(&mut self, asset_id: AssetId) {
+        let data = self.data::<MultiAssetData>();
+        let index = data.asset_count;
+        data.asset_index_to_id.insert(index, &asset_id);
+        data.asset_id_to_index.insert(asset_id, &index);
+        data.asset_count += 1;
+    }
+
+    /// Remove the asset id from the collection asset ids
+    default fn remove_collection_asset_id(&mut self, asset_id: &AssetId) {
+        let data = self.data::<MultiAssetData>();
+        let index = match data.asset_id_to_index.get(asset_id) {
+            Some(index) => index,
+            None => {
+                data.collection_asset_ids.retain(|id| id != asset_id);
+                return
+            }
+        };
+
+        let last_index = data.asset_count - 1;
+        if index != last_index {
+            if let Some(last_id) = data.asset_index_to_id.get(last_index) {
+                data.asset_index_to_id.insert(index, &last_id);
+                data.asset_id_to_index.insert(last_id, &index);
+            }
+        }
+        data.asset_index_to_id.remove(last_index);
+        data.asset_id_to_index.remove(asset_id);
+        data.asset_count = last_index;
+    }
+
+    /// Get the collection asset id at the index
+    default fn collection_asset_id_at(&self, index: u32) -> Option<AssetId> {
+        let data = self.data::<MultiAssetData>();
+        if index < data.asset_count {
+            return data.asset_index_to_id.get(index)
+        }
+        data.collection_asset_ids
+            .get((index - data.asset_count) as usize)
+            .copied()
+    }
+
+    /// Check that the asset exists and isn't deprecated
+    default fn ensure_asset_addable(&self, asset_id: AssetId) -> Result<()> {
+        let data = self.data::<MultiAssetData>();
+        data.collection_asset_entries
+            .get(asset_id)
+            .ok_or(RmrkError::AssetIdNotFound)?;
+        if data.deprecated_assets.get(asset_id).is_some() {
+            return Err(RmrkError::AssetIsDeprecated.into())
+        }
+        Ok(())
+    }
+
+    /// Count one more token using the asset
+    default fn increase_asset_usage(&mut self, asset_id: &AssetId) {
+        let data = self.data::<MultiAssetData>();
+        let usage = data.asset_usage.get(asset_id).unwrap_or(0);
+        data.asset_usage.insert(asset_id, &usage.saturating_add(1));
+    }
+
+    /// Count one less token using the asset
+    default fn decrease_asset_usage(&mut self, asset_id: &AssetId) {
+        let data = self.data::<MultiAssetData>();
+        let usage = data.asset_usage.get(asset_id).unwrap_or(0);
+        data.asset_usage.insert(asset_id, &usage.saturating_sub(1));
+    }
+
+    /// Count one more asset entry referencing each part of the local base
+    default fn increase_part_references(
+        &mut self,
+        base_address: &Option<AccountId>,
+        part_ids: &[PartId],
+    ) {
+        if base_address.map_or(false, |base| base != Self::env().account_id()) {
+            return
+        }
+        let data = self.data::<MultiAssetData>();
+        for part_id in part_ids {
+            let references = data.part_references.get(part_id).unwrap_or(0);
+            data.part_references
+                .insert(part_id, &references.saturating_add(1));
+        }
+    }
+
+    /// Count one less asset entry referencing each part of the local base
+    default fn decrease_part_references(
+        &mut self,
+        base_address: &Option<AccountId>,
+        part_ids: &[PartId],
+    ) {
+        if base_address.map_or(false, |base| base != Self::env().account_id()) {
+            return
+        }
+        let data = self.data::<MultiAssetData>();
+        for part_id in part_ids {
+            let references = data.part_references.get(part_id).unwrap_or(0);
+            data.part_references
+                .insert(part_id, &references.saturating_sub(1));
+        }
+    }
+
+    /// Check that the asset can be proposed as a replacement
+    default fn ensure_replaceable(&self, token_id: &Id, replaces_id: &AssetId) -> Result<()> {
+        let accepted_list = self
+            .data::<MultiAssetData>()
+            .accepted_assets
+            .get(token_id)
+            .ok_or(RmrkError::AcceptedAssetsMissing)?;
+        if !accepted_list.contains(replaces_id) {
+            return Err(RmrkError::InvalidAssetId.into())
+        }
+        Ok(())
+    }
+
+    /// Replace an accepted asset by another AssetId, keeping its position and priority
     default fn replace_asset(
         &mut self,
         token_id: &Id,
@@ -203,9 +487,10 @@ where
             .ok_or(RmrkError::InvalidAssetId)?;
 
         accepted_list[asset_index] = *asset_id;
-        self.data::<MultiAssetData>()
-            .accepted_assets
-            .insert(&token_id, &accepted_list);
+        let data = self.data::<MultiAssetData>();
+        data.accepted_assets.insert(&token_id, &accepted_list);
+        data.token_asset_uris.remove((token_id, replace_with_id));
+        self.decrease_asset_usage(replace_with_id);
 
         Ok(())
     }
@@ -13,7 +13,10 @@ use rmrk_common::{
         Result,
         RmrkError,
     },
-    roles::CONTRIBUTOR,
+    roles::{
+        ADMIN,
+        CONTRIBUTOR,
+    },
     types::*,
     utils::Utils,
 };
@@ -21,6 +24,8 @@ use rmrk_common::{
 use traits::{
     MultiAsset,
     MultiAssetEvents,
+    MultiAssetHooks,
+    MultiAssetTyped,
 };
 
 use ink_prelude::vec::Vec;
@@ -33,6 +38,7 @@ use openbrush::{
     },
     modifiers,
     traits::{
+        AccountId,
         Storage,
         String,
     },
@@ -40,13 +46,17 @@ use openbrush::{
 
 pub const STORAGE_MULTIASSET_KEY: u32 = openbrush::storage_unique_key!(MultiAssetData);
 
+/// Maximum number of pending assets per token, used until configured.
+pub const DEFAULT_MAX_PENDING_ASSETS: u32 = 128;
+
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_MULTIASSET_KEY)]
 pub struct MultiAssetData {
     /// Mapping of available asset entries for this collection
     pub collection_asset_entries: Mapping<AssetId, Asset>,
 
-    /// Collection asset id list
+    /// Collection asset id list, superseded by `asset_index_to_id`.
+    /// Only holds ids of contracts deployed before the indexed storage until they are migrated
     pub collection_asset_ids: Vec<AssetId>,
 
     /// Mapping of tokenId to an array of active assets
@@ -54,6 +64,56 @@ pub struct MultiAssetData {
 
     /// Mapping of tokenId to an array of pending assets
     pub pending_assets: Mapping<Id, Vec<AssetId>>,
+
+    /// Asset id assigned by the next `add_asset_entry_auto`
+    pub next_asset_id: AssetId,
+
+    /// Maximum number of pending assets per token (0 means default)
+    pub max_pending_assets: u32,
+
+    /// Mapping of (token owner, tokenId) to the account approved to manage the token's assets
+    pub asset_approvals: Mapping<(AccountId, Id), AccountId>,
+
+    /// Mapping of (token owner, operator) approved to manage all assets of the owner's tokens
+    pub asset_operators: Mapping<(AccountId, AccountId), ()>,
+
+    /// Mapping of tokenId to priorities of accepted assets, aligned with `accepted_assets`
+    pub asset_priorities: Mapping<Id, Vec<u16>>,
+
+    /// Mapping of (tokenId, pending assetId) to the accepted asset it will replace
+    pub asset_replacements: Mapping<(Id, AssetId), AssetId>,
+
+    /// Asset entries which can no longer be added to tokens
+    pub deprecated_assets: Mapping<AssetId, ()>,
+
+    /// Mapping of assetId to the number of tokens on which the asset is pending or accepted
+    pub asset_usage: Mapping<AssetId, u32>,
+
+    /// Mapping of assetId to its media type, used by `MultiAssetTyped`
+    pub asset_types: Mapping<AssetId, TypedAsset>,
+
+    /// Mapping of (tokenId, assetId) to the asset uri used by this token only
+    pub token_asset_uris: Mapping<(Id, AssetId), String>,
+
+    /// Number of indexed collection asset ids
+    pub asset_count: u32,
+
+    /// Mapping of index to the collection asset id stored at it
+    pub asset_index_to_id: Mapping<u32, AssetId>,
+
+    /// Mapping of collection asset id to its index
+    pub asset_id_to_index: Mapping<AssetId, u32>,
+
+    /// Mapping of local base partId to the number of asset entries referencing it
+    pub part_references: Mapping<PartId, u32>,
+
+    /// Mapping of assetId to the address of the base which holds the asset's parts.
+    /// Kept out of `Asset` so entries stored before it was added stay readable
+    pub asset_bases: Mapping<AssetId, AccountId>,
+
+    /// Asset entries added before `asset_usage` was counted, marked when their ids are migrated.
+    /// Tokens may use them without being counted, so they can't be removed
+    pub uncounted_assets: Mapping<AssetId, ()>,
 }
 
 impl<T> MultiAsset for T
@@ -69,25 +129,112 @@ where
         &mut self,
         asset_id: AssetId,
         equippable_group_id: EquippableGroupId,
+        base_address: Option<AccountId>,
         asset_uri: String,
         part_ids: Vec<PartId>,
     ) -> Result<()> {
         self.ensure_asset_id_is_available(asset_id)?;
-        self.data::<MultiAssetData>()
+        self._validate_asset_parts(&base_address, &part_ids)?;
+        self.insert_asset_entry(
+            asset_id,
+            equippable_group_id,
+            base_address,
+            asset_uri,
+            part_ids,
+        );
+
+        Ok(())
+    }
+
+    /// Used to add a asset entry with the next available asset id.
+    #[modifiers(only_role(CONTRIBUTOR))]
+    fn add_asset_entry_auto(
+        &mut self,
+        equippable_group_id: EquippableGroupId,
+        base_address: Option<AccountId>,
+        asset_uri: String,
+        part_ids: Vec<PartId>,
+    ) -> Result<AssetId> {
+        self._validate_asset_parts(&base_address, &part_ids)?;
+
+        // The counter is past every indexed id, ids not migrated yet are checked separately
+        let data = self.data::<MultiAssetData>();
+        let mut asset_id = data.next_asset_id.max(1);
+        if let Some(legacy_max) = data.collection_asset_ids.iter().max() {
+            let after_legacy = legacy_max
+                .checked_add(1)
+                .ok_or(RmrkError::AssetIdsExhausted)?;
+            asset_id = asset_id.max(after_legacy);
+        }
+        // The counter stops at the highest id
+        if self.ensure_asset_id_is_available(asset_id).is_err() {
+            return Err(RmrkError::AssetIdsExhausted.into())
+        }
+        self.insert_asset_entry(
+            asset_id,
+            equippable_group_id,
+            base_address,
+            asset_uri,
+            part_ids,
+        );
+
+        Ok(asset_id)
+    }
+
+    /// Used to update the uri of an asset entry.
+    #[modifiers(only_role(CONTRIBUTOR))]
+    fn set_asset_uri(&mut self, asset_id: AssetId, asset_uri: String) -> Result<()> {
+        let data = self.data::<MultiAssetData>();
+        let mut asset = data
             .collection_asset_entries
-            .insert(
-                asset_id,
-                &Asset {
-                    equippable_group_id,
-                    asset_uri,
-                    part_ids: part_ids.clone(),
-                },
-            );
-        self.data::<MultiAssetData>()
-            .collection_asset_ids
-            .push(asset_id);
-        self._emit_asset_set_event(&asset_id);
+            .get(asset_id)
+            .ok_or(RmrkError::AssetIdNotFound)?;
+        asset.asset_uri = asset_uri;
+        data.collection_asset_entries.insert(asset_id, &asset);
+
+        self._emit_asset_uri_set_event(&asset_id);
+        Ok(())
+    }
+
+    /// Used to deprecate an asset entry.
+    #[modifiers(only_role(CONTRIBUTOR))]
+    fn deprecate_asset(&mut self, asset_id: AssetId) -> Result<()> {
+        let data = self.data::<MultiAssetData>();
+        data.collection_asset_entries
+            .get(asset_id)
+            .ok_or(RmrkError::AssetIdNotFound)?;
+        data.deprecated_assets.insert(asset_id, &());
 
+        self._emit_asset_deprecated_event(&asset_id);
+        Ok(())
+    }
+
+    /// Used to remove an asset entry which isn't used by any token.
+    #[modifiers(only_role(CONTRIBUTOR))]
+    fn remove_asset_entry(&mut self, asset_id: AssetId) -> Result<()> {
+        let data = self.data::<MultiAssetData>();
+        let asset = data
+            .collection_asset_entries
+            .get(asset_id)
+            .ok_or(RmrkError::AssetIdNotFound)?;
+        // Tokens which had the asset before usage was counted aren't included in `asset_usage`
+        if data.asset_usage.get(asset_id).unwrap_or(0) > 0
+            || data.uncounted_assets.get(asset_id).is_some()
+            || data.collection_asset_ids.contains(&asset_id)
+        {
+            return Err(RmrkError::AssetInUse.into())
+        }
+
+        let base_address = data.asset_bases.get(asset_id);
+        self.decrease_part_references(&base_address, &asset.part_ids);
+        let data = self.data::<MultiAssetData>();
+        data.collection_asset_entries.remove(asset_id);
+        data.deprecated_assets.remove(asset_id);
+        data.asset_bases.remove(asset_id);
+        data.asset_types.remove(asset_id);
+        self.remove_collection_asset_id(&asset_id);
+
+        self._emit_asset_entry_removed_event(&asset_id);
         Ok(())
     }
 
@@ -102,98 +249,247 @@ where
         replaces_asset_with_id: Option<AssetId>,
     ) -> Result<()> {
         // Check if asset id is valid
-        self.data::<MultiAssetData>()
-            .collection_asset_entries
-            .get(asset_id)
-            .ok_or(RmrkError::AssetIdNotFound)?;
+        self.ensure_asset_addable(asset_id)?;
         let token_owner = self.ensure_exists_and_get_owner(&token_id)?;
         self.ensure_not_accepted(&token_id, &asset_id)?;
         self.ensure_not_pending(&token_id, &asset_id)?;
+        if let Some(replaces_id) = replaces_asset_with_id {
+            self.ensure_replaceable(&token_id, &replaces_id)?;
+        }
+
+        let caller = Self::env().caller();
+        let auto_accept =
+            caller == token_owner || self.is_asset_operator(&token_owner, &token_id, &caller);
+        if !auto_accept {
+            self.add_to_pending_assets(&token_id, &asset_id)?;
+        }
+        if let Some(replaces_id) = replaces_asset_with_id {
+            self.data::<MultiAssetData>()
+                .asset_replacements
+                .insert((&token_id, &asset_id), &replaces_id);
+        }
+        self.increase_asset_usage(&asset_id);
         self._emit_asset_added_to_token_event(&token_id, &asset_id, &replaces_asset_with_id);
 
-        if let Some(replace_with_id) = replaces_asset_with_id {
-            ink_env::debug_println!("replaces_asset_with_id {:?}", &replaces_asset_with_id);
-            return self.replace_asset(&token_id, &asset_id, &replace_with_id)
-        } else {
-            let caller = Self::env().caller();
-            // If the asset is being added by the current root owner of the token, the asset will be automatically accepted.
-            if caller == token_owner {
-                self.add_to_accepted_assets(&token_id, &asset_id);
-            } else {
-                self.add_to_pending_assets(&token_id, &asset_id);
-            }
+        // If the asset is being added by the current root owner of the token, or by an account approved
+        // to manage the token's assets, the asset will be automatically accepted.
+        if auto_accept {
+            self.add_to_accepted_assets(&token_id, &asset_id);
         }
 
         Ok(())
     }
 
+    /// Used to add an asset to many tokens.
+    #[modifiers(only_role(CONTRIBUTOR))]
+    fn add_asset_to_tokens(
+        &mut self,
+        asset_id: AssetId,
+        token_ids: Vec<Id>,
+        replaces_asset_with_id: Option<AssetId>,
+    ) -> Result<Vec<(Id, Result<()>)>> {
+        self.ensure_asset_addable(asset_id)?;
+
+        let mut outcomes = Vec::with_capacity(token_ids.len());
+        for token_id in token_ids {
+            let outcome =
+                self.add_asset_to_token(token_id.clone(), asset_id, replaces_asset_with_id);
+            outcomes.push((token_id, outcome));
+        }
+        Ok(outcomes)
+    }
+
     /// Accepts an asset from the pending array of given token.
     fn accept_asset(&mut self, token_id: Id, asset_id: AssetId) -> Result<()> {
         self.ensure_pending(&token_id, &asset_id)?;
-        let token_owner = self.ensure_exists_and_get_owner(&token_id)?;
-        let caller = Self::env().caller();
-        if caller == token_owner {
-            self.remove_from_pending_assets(&token_id, &asset_id)?;
-            self.add_to_accepted_assets(&token_id, &asset_id);
-        } else {
-            return Err(RmrkError::NotTokenOwner.into())
-        }
+        self.ensure_asset_manager(&token_id)?;
+
+        self.remove_from_pending_assets(&token_id, &asset_id)?;
+        self.add_to_accepted_assets(&token_id, &asset_id);
         Ok(())
     }
 
     /// Rejects an asset from the pending array of given token.
     fn reject_asset(&mut self, token_id: Id, asset_id: AssetId) -> Result<()> {
         self.ensure_pending(&token_id, &asset_id)?;
-        let token_owner = self.ensure_exists_and_get_owner(&token_id)?;
-        self.ensure_token_owner(token_owner)?;
+        self.ensure_asset_manager(&token_id)?;
 
         self.remove_from_pending_assets(&token_id, &asset_id)?;
+        let data = self.data::<MultiAssetData>();
+        data.asset_replacements.remove((&token_id, &asset_id));
+        data.token_asset_uris.remove((&token_id, &asset_id));
+        self.decrease_asset_usage(&asset_id);
 
         self._emit_asset_rejected_event(&token_id, &asset_id);
         Ok(())
     }
 
+    /// Rejects all assets from the pending array of given token.
+    fn reject_all_assets(&mut self, token_id: Id, max_rejections: u32) -> Result<u64> {
+        self.ensure_asset_manager(&token_id)?;
+
+        let assets = self
+            .data::<MultiAssetData>()
+            .pending_assets
+            .get(&token_id)
+            .unwrap_or_default();
+        // Protects the owner from rejecting assets added after the call was submitted
+        if assets.len() > max_rejections as usize {
+            return Err(RmrkError::UnexpectedNumberOfAssets.into())
+        }
+        self.data::<MultiAssetData>()
+            .pending_assets
+            .remove(&token_id);
+
+        for asset_id in assets.iter() {
+            let data = self.data::<MultiAssetData>();
+            data.asset_replacements.remove((&token_id, asset_id));
+            data.token_asset_uris.remove((&token_id, asset_id));
+            self.decrease_asset_usage(asset_id);
+            self._emit_asset_rejected_event(&token_id, asset_id);
+        }
+        Ok(assets.len() as u64)
+    }
+
     /// Remove an asset from the pending array of given token.
     fn remove_asset(&mut self, token_id: Id, asset_id: AssetId) -> Result<()> {
         self.ensure_asset_accepted(&token_id, &asset_id)?;
-        let token_owner = self.ensure_exists_and_get_owner(&token_id)?;
-        self.ensure_token_owner(token_owner)?;
+        self.ensure_asset_manager(&token_id)?;
 
         self.remove_from_accepted_assets(&token_id, &asset_id)?;
+        self.decrease_asset_usage(&asset_id);
 
         self._emit_asset_removed_event(&token_id, &asset_id);
         Ok(())
     }
 
     /// Used to specify the priorities for a given token's active assets.
-    fn set_priority(&mut self, token_id: Id, priorities: Vec<AssetId>) -> Result<()> {
-        let token_owner = self.ensure_exists_and_get_owner(&token_id)?;
-        self.ensure_token_owner(token_owner)?;
-        if let Some(accepted_assets) = self
+    fn set_priority(&mut self, token_id: Id, priorities: Vec<u16>) -> Result<()> {
+        self.ensure_asset_manager(&token_id)?;
+        let accepted_assets = self
             .data::<MultiAssetData>()
             .accepted_assets
-            .get(token_id.clone())
-        {
-            if accepted_assets.len() != priorities.len() {
-                return Err(RmrkError::BadPriorityLength.into())
-            }
-            for asset in priorities.clone() {
-                if !accepted_assets.contains(&asset) {
-                    return Err(RmrkError::AssetIdNotFound.into())
-                }
-            }
+            .get(&token_id)
+            .unwrap_or_default();
+        if accepted_assets.len() != priorities.len() {
+            return Err(RmrkError::BadPriorityLength.into())
         }
 
         self.data::<MultiAssetData>()
-            .accepted_assets
+            .asset_priorities
             .insert(&token_id, &priorities);
         self._emit_asset_priority_set_event(&token_id, priorities);
         Ok(())
     }
 
+    /// Used to retrieve the priorities of the token's active assets
+    default fn get_active_asset_priorities(&self, token_id: Id) -> Result<Vec<u16>> {
+        self.ensure_exists_and_get_owner(&token_id)?;
+        Ok(self.asset_priorities(&token_id))
+    }
+
+    /// Used to retrieve the accepted asset which the pending asset will replace
+    default fn get_asset_replacement(&self, token_id: Id, asset_id: AssetId) -> Option<AssetId> {
+        self.data::<MultiAssetData>()
+            .asset_replacements
+            .get((&token_id, &asset_id))
+    }
+
+    /// Used to retrieve the active asset with the highest priority
+    default fn get_top_asset(&self, token_id: Id) -> Result<Option<AssetId>> {
+        self.ensure_exists_and_get_owner(&token_id)?;
+        let accepted_assets = self
+            .data::<MultiAssetData>()
+            .accepted_assets
+            .get(&token_id)
+            .unwrap_or_default();
+        let top_asset = self
+            .asset_priorities(&token_id)
+            .iter()
+            .zip(accepted_assets.iter())
+            .min_by_key(|(priority, _)| **priority)
+            .map(|(_, asset_id)| *asset_id);
+        Ok(top_asset)
+    }
+
+    /// Approve an account to manage the assets of the token
+    default fn approve_for_assets(
+        &mut self,
+        operator: Option<AccountId>,
+        token_id: Id,
+    ) -> Result<()> {
+        let token_owner = self.ensure_exists_and_get_owner(&token_id)?;
+        let caller = Self::env().caller();
+        if caller != token_owner && !self.is_approved_for_all_for_assets(token_owner, caller) {
+            return Err(RmrkError::NotTokenOwner.into())
+        }
+
+        let data = self.data::<MultiAssetData>();
+        match operator {
+            Some(operator) => {
+                data.asset_approvals
+                    .insert((&token_owner, &token_id), &operator)
+            }
+            None => data.asset_approvals.remove((&token_owner, &token_id)),
+        }
+        self._emit_approval_for_assets_event(&token_owner, &operator, &token_id);
+        Ok(())
+    }
+
+    /// Approve or remove an operator for the assets of all caller's tokens
+    default fn set_approval_for_all_for_assets(
+        &mut self,
+        operator: AccountId,
+        approved: bool,
+    ) -> Result<()> {
+        let caller = Self::env().caller();
+        let data = self.data::<MultiAssetData>();
+        if approved {
+            data.asset_operators.insert((&caller, &operator), &());
+        } else {
+            data.asset_operators.remove((&caller, &operator));
+        }
+        self._emit_approval_for_all_for_assets_event(&caller, &operator, approved);
+        Ok(())
+    }
+
+    /// Get the account approved to manage the assets of the token
+    default fn get_approved_for_assets(&self, token_id: Id) -> Result<Option<AccountId>> {
+        let token_owner = self.ensure_exists_and_get_owner(&token_id)?;
+        Ok(self
+            .data::<MultiAssetData>()
+            .asset_approvals
+            .get((&token_owner, &token_id)))
+    }
+
+    /// Check if the operator is approved to manage the assets of all owner's tokens
+    default fn is_approved_for_all_for_assets(
+        &self,
+        owner: AccountId,
+        operator: AccountId,
+    ) -> bool {
+        self.data::<MultiAssetData>()
+            .asset_operators
+            .get((&owner, &operator))
+            .is_some()
+    }
+
+    /// Set the maximum number of pending assets per token
+    #[modifiers(only_role(CONTRIBUTOR))]
+    default fn set_max_pending_assets(&mut self, max_pending: u32) -> Result<()> {
+        self.data::<MultiAssetData>().max_pending_assets = max_pending;
+        Ok(())
+    }
+
+    /// Read the maximum number of pending assets per token
+    default fn get_max_pending_assets(&self) -> u32 {
+        self.max_pending_assets()
+    }
+
     /// Used to retrieve the total number of asset entries
     fn total_assets(&self) -> u32 {
-        self.data::<MultiAssetData>().collection_asset_ids.len() as u32
+        let data = self.data::<MultiAssetData>();
+        data.asset_count + data.collection_asset_ids.len() as u32
     }
 
     /// Used to retrieve the total number of assets per token
@@ -228,6 +524,87 @@ where
         return Ok(())
     }
 
+    /// Check that no asset entry references the part of the local base.
+    default fn ensure_part_unreferenced(&self, part_id: PartId) -> Result<()> {
+        let data = self.data::<MultiAssetData>();
+        // Entries whose ids aren't migrated yet aren't counted in `part_references`.
+        // They were added before base addresses, so their parts are in the local base
+        let referenced_by_unmigrated = data.collection_asset_ids.iter().any(|asset_id| {
+            data.collection_asset_entries
+                .get(asset_id)
+                .map_or(false, |asset| asset.part_ids.contains(&part_id))
+        });
+        if referenced_by_unmigrated || data.part_references.get(part_id).unwrap_or(0) > 0 {
+            return Err(RmrkError::PartInUse.into())
+        }
+        Ok(())
+    }
+
+    /// Clear the asset approval given by the previous owner of the token.
+    default fn clear_asset_approval(&mut self, from: Option<&AccountId>, token_id: &Id) {
+        if let Some(from) = from {
+            self.data::<MultiAssetData>()
+                .asset_approvals
+                .remove((from, token_id));
+        }
+    }
+
+    /// Remove all assets of a burned token and decrease their usage.
+    default fn release_token_assets(&mut self, token_id: &Id) {
+        let data = self.data::<MultiAssetData>();
+        let mut asset_ids = data.pending_assets.get(token_id).unwrap_or_default();
+        asset_ids.extend(data.accepted_assets.get(token_id).unwrap_or_default());
+        data.pending_assets.remove(token_id);
+        data.accepted_assets.remove(token_id);
+        data.asset_priorities.remove(token_id);
+
+        for asset_id in asset_ids.iter() {
+            let data = self.data::<MultiAssetData>();
+            data.asset_replacements.remove((token_id, asset_id));
+            data.token_asset_uris.remove((token_id, asset_id));
+            self.decrease_asset_usage(asset_id);
+        }
+    }
+
+    /// Used to set the uri of an asset for a single token
+    default fn set_token_asset_uri(
+        &mut self,
+        token_id: Id,
+        asset_id: AssetId,
+        asset_uri: Option<String>,
+    ) -> Result<()> {
+        let token_owner = self.ensure_exists_and_get_owner(&token_id)?;
+        let caller = Self::env().caller();
+        if !self.has_role(CONTRIBUTOR, caller)
+            && !self.is_asset_operator(&token_owner, &token_id, &caller)
+        {
+            return Err(AccessControlError::MissingRole.into())
+        }
+        self.ensure_asset_on_token(&token_id, &asset_id)?;
+
+        let data = self.data::<MultiAssetData>();
+        match asset_uri {
+            Some(asset_uri) => {
+                data.token_asset_uris
+                    .insert((&token_id, &asset_id), &asset_uri)
+            }
+            None => data.token_asset_uris.remove((&token_id, &asset_id)),
+        }
+        self._emit_token_asset_uri_set_event(&token_id, &asset_id);
+        Ok(())
+    }
+
+    /// Used to retrieve the uri of an asset for the token
+    default fn get_token_asset_uri(&self, token_id: Id, asset_id: AssetId) -> Option<String> {
+        if self.ensure_asset_on_token(&token_id, &asset_id).is_err() {
+            return None
+        }
+        self.data::<MultiAssetData>()
+            .token_asset_uris
+            .get((&token_id, &asset_id))
+            .or_else(|| self.get_asset_uri(asset_id))
+    }
+
     /// Used to retrieve asset's uri
     default fn get_asset_uri(&self, asset_id: AssetId) -> Option<String> {
         if let Some(asset) = self
@@ -245,6 +622,160 @@ where
         self.ensure_exists_and_get_owner(&token_id)?;
         Ok(self.data::<MultiAssetData>().accepted_assets.get(&token_id))
     }
+
+    /// Fetch all pending assets for the token_id
+    fn get_pending_token_assets(&self, token_id: Id) -> Result<Option<Vec<AssetId>>> {
+        self.ensure_exists_and_get_owner(&token_id)?;
+        Ok(self.data::<MultiAssetData>().pending_assets.get(&token_id))
+    }
+
+    /// Check if the asset entry is deprecated
+    default fn is_asset_deprecated(&self, asset_id: AssetId) -> bool {
+        self.data::<MultiAssetData>()
+            .deprecated_assets
+            .get(asset_id)
+            .is_some()
+    }
+
+    /// Used to retrieve the full asset entry
+    default fn get_asset(&self, asset_id: AssetId) -> Option<Asset> {
+        self.data::<MultiAssetData>()
+            .collection_asset_entries
+            .get(asset_id)
+    }
+
+    /// Used to retrieve the address of the base which holds the asset's parts
+    default fn get_asset_base(&self, asset_id: AssetId) -> Option<AccountId> {
+        self.data::<MultiAssetData>().asset_bases.get(asset_id)
+    }
+
+    /// Used to retrieve a page of the collection's asset ids
+    default fn get_collection_asset_ids(&self, offset: u32, limit: u32) -> Vec<AssetId> {
+        let end = offset.saturating_add(limit).min(self.total_assets());
+        (offset..end)
+            .filter_map(|index| self.collection_asset_id_at(index))
+            .collect()
+    }
+
+    /// Move asset ids of the legacy list to the indexed storage
+    #[modifiers(only_role(ADMIN))]
+    default fn migrate_collection_asset_ids(&mut self, limit: u32) -> Result<u32> {
+        let data = self.data::<MultiAssetData>();
+        let count = (limit as usize).min(data.collection_asset_ids.len());
+        let migrated: Vec<AssetId> = data.collection_asset_ids.drain(..count).collect();
+        for asset_id in migrated {
+            let data = self.data::<MultiAssetData>();
+            data.uncounted_assets.insert(asset_id, &());
+            if asset_id >= data.next_asset_id {
+                data.next_asset_id = asset_id.saturating_add(1);
+            }
+            // Parts of entries added before references were counted
+            if let Some(asset) = data.collection_asset_entries.get(asset_id) {
+                let base_address = data.asset_bases.get(asset_id);
+                self.increase_part_references(&base_address, &asset.part_ids);
+            }
+            self.push_collection_asset_id(asset_id);
+        }
+        Ok(self.data::<MultiAssetData>().collection_asset_ids.len() as u32)
+    }
+}
+
+impl<T> MultiAssetTyped for T
+where
+    T: Storage<MultiAssetData>
+        + Storage<psp34::Data<enumerable::Balances>>
+        + Storage<access_control::Data>
+        + Utils,
+{
+    /// Used to set the media type of an asset entry.
+    #[modifiers(only_role(CONTRIBUTOR))]
+    default fn set_asset_type(
+        &mut self,
+        asset_id: AssetId,
+        media_type: AssetMediaType,
+        thumbnail_uri: Option<String>,
+    ) -> Result<()> {
+        let data = self.data::<MultiAssetData>();
+        data.collection_asset_entries
+            .get(asset_id)
+            .ok_or(RmrkError::AssetIdNotFound)?;
+        data.asset_types.insert(
+            asset_id,
+            &TypedAsset {
+                media_type,
+                thumbnail_uri,
+            },
+        );
+        Ok(())
+    }
+
+    /// Used to retrieve the media type of an asset entry.
+    default fn get_asset_type(&self, asset_id: AssetId) -> Option<TypedAsset> {
+        self.data::<MultiAssetData>().asset_types.get(asset_id)
+    }
+
+    /// Fetch accepted assets of the token with the given media type
+    default fn get_accepted_assets_of_type(
+        &self,
+        token_id: Id,
+        media_type: AssetMediaType,
+    ) -> Result<Vec<AssetId>> {
+        self.ensure_exists_and_get_owner(&token_id)?;
+        let data = self.data::<MultiAssetData>();
+        let assets = data
+            .accepted_assets
+            .get(&token_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|asset_id| {
+                data.asset_types
+                    .get(asset_id)
+                    .map_or(false, |typed| typed.media_type == media_type)
+            })
+            .collect();
+        Ok(assets)
+    }
+
+    /// Used to retrieve the accepted asset with the given media type and the highest priority
+    default fn get_top_asset_of_type(
+        &self,
+        token_id: Id,
+        media_type: AssetMediaType,
+    ) -> Result<Option<AssetId>> {
+        self.ensure_exists_and_get_owner(&token_id)?;
+        let data = self.data::<MultiAssetData>();
+        let accepted_assets = data.accepted_assets.get(&token_id).unwrap_or_default();
+        let top_asset = self
+            .asset_priorities(&token_id)
+            .iter()
+            .zip(accepted_assets.iter())
+            .filter(|(_, asset_id)| {
+                data.asset_types
+                    .get(*asset_id)
+                    .map_or(false, |typed| typed.media_type == media_type)
+            })
+            .min_by_key(|(priority, _)| **priority)
+            .map(|(_, asset_id)| *asset_id);
+        Ok(top_asset)
+    }
+}
+
+/// Hooks trait for MultiAssets
+impl<T> MultiAssetHooks for T
+where
+    T: Storage<MultiAssetData>,
+{
+    /// Without a base module, only an asset referencing a base by address can have parts
+    default fn _validate_asset_parts(
+        &self,
+        base_address: &Option<AccountId>,
+        part_ids: &Vec<PartId>,
+    ) -> Result<()> {
+        if !part_ids.is_empty() && base_address.is_none() {
+            return Err(RmrkError::AssetHasNoBase.into())
+        }
+        Ok(())
+    }
 }
 
 /// Event trait for MultiAssets
@@ -265,7 +796,13 @@ where
     }
 
     /// Used to notify listeners that an asset object at `assetId` is accepted by the token and migrated
-    default fn _emit_asset_accepted_event(&self, _token_id: &Id, _asset_id: &AssetId) {}
+    default fn _emit_asset_accepted_event(
+        &self,
+        _token_id: &Id,
+        _asset_id: &AssetId,
+        _replaces_id: &Option<AssetId>,
+    ) {
+    }
 
     /// Used to notify listeners that an asset object at `assetId` is rejected from token and is dropped from the pending assets array of the token.
     default fn _emit_asset_rejected_event(&self, _token_id: &Id, _asset_id: &AssetId) {}
@@ -274,5 +811,35 @@ where
     default fn _emit_asset_removed_event(&self, _token_id: &Id, _asset_id: &AssetId) {}
 
     /// Used to notify listeners that token's prioritiy array is reordered.
-    default fn _emit_asset_priority_set_event(&self, _token_id: &Id, _priorities: Vec<AssetId>) {}
+    default fn _emit_asset_priority_set_event(&self, _token_id: &Id, _priorities: Vec<u16>) {}
+
+    /// Used to notify listeners that the uri of an asset entry is updated.
+    default fn _emit_asset_uri_set_event(&self, _asset_id: &AssetId) {}
+
+    /// Used to notify listeners that the uri of an asset is set for a single token.
+    default fn _emit_token_asset_uri_set_event(&self, _token_id: &Id, _asset_id: &AssetId) {}
+
+    /// Used to notify listeners that an asset entry can no longer be added to tokens.
+    default fn _emit_asset_deprecated_event(&self, _asset_id: &AssetId) {}
+
+    /// Used to notify listeners that an asset entry is removed from the collection.
+    default fn _emit_asset_entry_removed_event(&self, _asset_id: &AssetId) {}
+
+    /// Used to notify listeners that an account is approved to manage the token's assets.
+    default fn _emit_approval_for_assets_event(
+        &self,
+        _owner: &AccountId,
+        _operator: &Option<AccountId>,
+        _token_id: &Id,
+    ) {
+    }
+
+    /// Used to notify listeners that an operator is approved or removed for all owner's assets.
+    default fn _emit_approval_for_all_for_assets_event(
+        &self,
+        _owner: &AccountId,
+        _operator: &AccountId,
+        _approved: bool,
+    ) {
+    }
 }
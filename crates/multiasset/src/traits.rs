@@ -7,7 +7,10 @@ use rmrk_common::{
 use ink_prelude::vec::Vec;
 use openbrush::{
     contracts::psp34::Id,
-    traits::String,
+    traits::{
+        AccountId,
+        String,
+    },
 };
 
 #[openbrush::wrapper]
@@ -16,33 +19,101 @@ pub type MultiAssetRef = dyn MultiAsset;
 /// Trait definitions for MultiAsset ink! messages
 #[openbrush::trait_definition]
 pub trait MultiAsset {
-    /// Used to add a asset entry.
-    /// The ID of the asset is automatically assigned to be the next available asset ID.
+    /// Used to add a asset entry with a given ID, e.g. when importing assets.
+    /// If the ID is already used, the execution will be reverted.
+    /// Use `add_asset_entry_auto` to get the next available asset ID assigned.
+    /// The part IDs are validated against the base which holds the parts. If the base module is
+    /// composed into this contract, `None` refers to the local base; otherwise assets with parts must
+    /// reference a base by address.
     /// # Arguments
+    ///  * `id` ID of the new asset
+    ///  * `equippable_group_id` ID of the equippable group
+    ///  * `base_address` Address of the base which holds the parts, `None` for the local base
     ///  * `asset_uri` Uri for the new asset
+    ///  * `part_ids` IDs of fixed and slot parts of the asset
     /// Emits an {AssetSet} event.
     #[ink(message)]
     fn add_asset_entry(
         &mut self,
         id: AssetId,
         equippable_group_id: EquippableGroupId,
+        base_address: Option<AccountId>,
         asset_uri: String,
         part_ids: Vec<PartId>,
     ) -> Result<()>;
 
+    /// Used to add a asset entry.
+    /// The ID of the asset is automatically assigned to be the next available asset ID.
+    /// The part IDs are validated as in `add_asset_entry`.
+    /// Fails with `AssetIdsExhausted` if no asset ID above the highest one in use is free.
+    /// # Arguments
+    ///  * `equippable_group_id` ID of the equippable group
+    ///  * `base_address` Address of the base which holds the parts, `None` for the local base
+    ///  * `asset_uri` Uri for the new asset
+    ///  * `part_ids` IDs of fixed and slot parts of the asset
+    /// # Returns
+    ///  * ID of the new asset
+    /// Emits an {AssetSet} event.
+    #[ink(message)]
+    fn add_asset_entry_auto(
+        &mut self,
+        equippable_group_id: EquippableGroupId,
+        base_address: Option<AccountId>,
+        asset_uri: String,
+        part_ids: Vec<PartId>,
+    ) -> Result<AssetId>;
+
+    /// Used to update the uri of an asset entry, e.g. to fix a broken link.
+    /// The new uri is used by every token which has the asset.
+    /// # Requirements:
+    ///  * Caller must have `CONTRIBUTOR` role
+    ///  * `assetId` must exist.
+    /// # Arguments
+    ///  * assetId ID of the asset to update
+    ///  * assetUri New uri of the asset
+    /// Emits an {AssetUriSet} event.
+    #[ink(message)]
+    fn set_asset_uri(&mut self, asset_id: AssetId, asset_uri: String) -> Result<()>;
+
+    /// Used to deprecate an asset entry.
+    /// A deprecated asset can no longer be added to tokens, but tokens which already have it keep it.
+    /// # Requirements:
+    ///  * Caller must have `CONTRIBUTOR` role
+    ///  * `assetId` must exist.
+    /// # Arguments
+    ///  * assetId ID of the asset to deprecate
+    /// Emits an {AssetDeprecated} event.
+    #[ink(message)]
+    fn deprecate_asset(&mut self, asset_id: AssetId) -> Result<()>;
+
+    /// Used to remove an asset entry from the collection.
+    /// Only assets which are neither pending nor accepted on any token can be removed.
+    /// Entries added before the usage count was introduced can't be removed, since tokens may
+    /// use them without being counted. Deprecate them instead.
+    /// # Requirements:
+    ///  * Caller must have `CONTRIBUTOR` role
+    ///  * `assetId` must exist and must not be used by any token.
+    /// # Arguments
+    ///  * assetId ID of the asset to remove
+    /// Emits an {AssetEntryRemoved} event.
+    #[ink(message)]
+    fn remove_asset_entry(&mut self, asset_id: AssetId) -> Result<()>;
+
     /// Used to add an asset to a token.
     /// If the given asset is already added to the token, the execution will be reverted.
-    /// If the asset ID is invalid, the execution will be reverted.
-    /// If the token already has the maximum amount of pending assets (128), the execution will be
-    /// reverted.
-    /// If the asset is being added by the current root owner of the token, the asset will be automatically
-    /// accepted.
+    /// If the asset ID is invalid or the asset is deprecated, the execution will be reverted.
+    /// If the token already has the maximum amount of pending assets (128 unless configured with
+    /// `set_max_pending_assets`), the execution will be reverted with `MaxPendingAssetsReached`.
+    /// If the asset is being added by the current root owner of the token, or by an account approved
+    /// to manage the token's assets, the asset will be automatically accepted.
+    /// A replacement is only a proposal: the active asset is replaced when the new asset is accepted,
+    /// keeping its position and priority. If the replaced asset is no longer active by then, the new
+    /// asset is added as any other accepted asset.
     /// # Arguments
     ///  * tokenId ID of the token to add the asset to
     ///  * assetId ID of the asset to add to the token
-    ///  * replacesAssetWithId ID of the asset to replace from the token's list of active assets
-    /// Emits an {AssetAddedToToken} event.
-
+    ///  * replacesAssetWithId ID of the active asset to replace from the token's list of active assets
+    /// Emits an {AssetAddedToToken} event, and an {AssetAccepted} event if the asset is accepted.
     #[ink(message)]
     fn add_asset_to_token(
         &mut self,
@@ -51,9 +122,31 @@ pub trait MultiAsset {
         replaces_asset_with_id: Option<AssetId>,
     ) -> Result<()>;
 
+    /// Used to add an asset to many tokens, e.g. when rolling out a new asset to the whole collection.
+    /// Each token is handled as with `add_asset_to_token`. A token which can't receive the asset
+    /// (e.g. it already has the asset) is skipped and reported, without failing the whole batch.
+    /// # Requirements:
+    ///  * Caller must have `CONTRIBUTOR` role
+    ///  * `assetId` must exist.
+    /// # Arguments
+    ///  * assetId ID of the asset to add to the tokens
+    ///  * tokenIds IDs of the tokens to add the asset to
+    ///  * replacesAssetWithId ID of the active asset to replace on each token
+    /// # Returns
+    ///  * Outcome of adding the asset, per token
+    /// Emits an {AssetAddedToToken} event for each token which received the asset.
+    #[ink(message)]
+    fn add_asset_to_tokens(
+        &mut self,
+        asset_id: AssetId,
+        token_ids: Vec<Id>,
+        replaces_asset_with_id: Option<AssetId>,
+    ) -> Result<Vec<(Id, Result<()>)>>;
+
     /// Accepts an asset at from the pending array of given token.
     /// Migrates the asset from the token's pending asset array to the token's active asset array.
     /// Active assets cannot be removed by anyone, but can be replaced by a new asset.
+    /// If the asset was added as a replacement, the replaced asset is swapped out now.
     /// # Requirements:
     ///  * The caller must own the token or be approved to manage the token's assets
     ///  * `tokenId` must exist.
@@ -78,17 +171,102 @@ pub trait MultiAsset {
     #[ink(message)]
     fn reject_asset(&mut self, token_id: Id, asset_id: AssetId) -> Result<()>;
 
+    /// Rejects all assets from the pending array of given token.
+    /// Clears the token's pending asset array.
+    /// # Requirements:
+    ///  * The caller must own the token or be approved to manage the token's assets
+    ///  * `tokenId` must exist.
+    ///  * The token must not have more than `maxRejections` pending assets. This protects the
+    ///    owner from rejecting assets added after the call was submitted.
+    /// # Arguments
+    ///  * tokenId ID of the token for which to reject all pending assets
+    ///  * maxRejections Maximum number of expected assets to reject
+    /// # Returns
+    ///  * u64 The number of rejected assets
+    /// Emits a {AssetRejected} event for each rejected asset.
+    #[ink(message)]
+    fn reject_all_assets(&mut self, token_id: Id, max_rejections: u32) -> Result<u64>;
+
     /// Used to specify the priorities for a given token's active assets.
     /// If the length of the priorities array doesn't match the length of the active assets array, the execution
     ///  will be reverted.
     /// The position of the priority value in the array corresponds the position of the asset in the active
-    ///  assets array it will be applied to.
+    ///  assets array it will be applied to. A lower value means a higher priority.
+    /// Newly accepted assets get the priority equal to their position in the active assets array.
+    /// The caller must own the token or be approved to manage the token's assets.
     /// # Arguments
     ///  * tokenId ID of the token for which the priorities are being set
     ///  * priorities Array of priorities for the assets
     /// Emits a {AssetPrioritySet} event.
     #[ink(message)]
-    fn set_priority(&mut self, token_id: Id, priorities: Vec<AssetId>) -> Result<()>;
+    fn set_priority(&mut self, token_id: Id, priorities: Vec<u16>) -> Result<()>;
+
+    /// Used to retrieve the priorities of the token's active assets.
+    /// The position of the priority value in the array corresponds the position of the asset in the active
+    ///  assets array returned by `get_accepted_token_assets`.
+    #[ink(message)]
+    fn get_active_asset_priorities(&self, token_id: Id) -> Result<Vec<u16>>;
+
+    /// Used to retrieve the accepted asset which the pending asset will replace once accepted.
+    /// # Returns
+    ///  * `None` if the asset was added without a replacement
+    #[ink(message)]
+    fn get_asset_replacement(&self, token_id: Id, asset_id: AssetId) -> Option<AssetId>;
+
+    /// Used to retrieve the active asset with the highest priority (the lowest priority value).
+    /// If several assets share the same priority, the first one in the active assets array is returned.
+    /// # Returns
+    ///  * `None` if the token has no active assets
+    #[ink(message)]
+    fn get_top_asset(&self, token_id: Id) -> Result<Option<AssetId>>;
+
+    /// Used to approve an account to manage the assets of the token.
+    /// The approved account can accept, reject, remove and prioritize the token's assets,
+    /// but can't transfer the token. The approval is cleared when the token changes owner,
+    /// see `clear_asset_approval`.
+    /// # Requirements:
+    ///  * The caller must own the token or be approved for all assets of the token owner
+    ///  * `tokenId` must exist.
+    /// # Arguments
+    ///  * operator Account to approve, `None` removes the current approval
+    ///  * tokenId ID of the token whose assets can be managed
+    /// Emits an {ApprovalForAssets} event.
+    #[ink(message)]
+    fn approve_for_assets(&mut self, operator: Option<AccountId>, token_id: Id) -> Result<()>;
+
+    /// Used to approve or remove an operator for the assets of all caller's tokens.
+    /// # Arguments
+    ///  * operator Account to add or remove as an operator
+    ///  * approved `true` to approve the operator, `false` to remove it
+    /// Emits an {ApprovalForAllForAssets} event.
+    #[ink(message)]
+    fn set_approval_for_all_for_assets(
+        &mut self,
+        operator: AccountId,
+        approved: bool,
+    ) -> Result<()>;
+
+    /// Used to retrieve the account approved to manage the assets of the token.
+    #[ink(message)]
+    fn get_approved_for_assets(&self, token_id: Id) -> Result<Option<AccountId>>;
+
+    /// Used to check if the operator is approved to manage the assets of all owner's tokens.
+    #[ink(message)]
+    fn is_approved_for_all_for_assets(&self, owner: AccountId, operator: AccountId) -> bool;
+
+    /// Set the maximum number of pending assets per token.
+    /// Adding an asset beyond the limit fails with `MaxPendingAssetsReached`.
+    /// Zero restores the default limit.
+    /// # Requirements:
+    ///  * Caller must have `CONTRIBUTOR` role
+    /// # Arguments
+    ///  * maxPending Maximum number of pending assets per token
+    #[ink(message)]
+    fn set_max_pending_assets(&mut self, max_pending: u32) -> Result<()>;
+
+    /// Used to retrieve the maximum number of pending assets per token.
+    #[ink(message)]
+    fn get_max_pending_assets(&self) -> u32;
 
     /// Used to retrieve the total number of assets.
     /// # Returns
@@ -96,6 +274,31 @@ pub trait MultiAsset {
     #[ink(message)]
     fn total_assets(&self) -> u32;
 
+    /// Used to set the uri of an asset for a single token, e.g. for art which evolves with the token.
+    /// The uri is cleared when the asset is rejected, removed or replaced on the token.
+    /// # Requirements:
+    ///  * Caller must have `CONTRIBUTOR` role or be approved to manage the token's assets
+    ///  * `tokenId` must exist.
+    ///  * `assetId` must be pending or accepted on the token.
+    /// # Arguments
+    ///  * tokenId ID of the token
+    ///  * assetId ID of the asset
+    ///  * assetUri Uri of the asset for this token, `None` restores the asset's uri
+    /// Emits a {TokenAssetUriSet} event.
+    #[ink(message)]
+    fn set_token_asset_uri(
+        &mut self,
+        token_id: Id,
+        asset_id: AssetId,
+        asset_uri: Option<String>,
+    ) -> Result<()>;
+
+    /// Used to retrieve the uri of an asset for the token.
+    /// Falls back to the asset's uri if the token has no uri of its own.
+    /// Returns `None` if the asset is neither pending nor accepted on the token.
+    #[ink(message)]
+    fn get_token_asset_uri(&self, token_id: Id, asset_id: AssetId) -> Option<String>;
+
     /// Used to retrieve asset's uri
     #[ink(message)]
     fn get_asset_uri(&self, asset_id: AssetId) -> Option<String>;
@@ -108,12 +311,128 @@ pub trait MultiAsset {
     #[ink(message)]
     fn get_accepted_token_assets(&self, token_id: Id) -> Result<Option<Vec<AssetId>>>;
 
+    /// Fetch all pending assets for the token_id
+    #[ink(message)]
+    fn get_pending_token_assets(&self, token_id: Id) -> Result<Option<Vec<AssetId>>>;
+
+    /// Used to check if the asset entry is deprecated.
+    #[ink(message)]
+    fn is_asset_deprecated(&self, asset_id: AssetId) -> bool;
+
+    /// Used to retrieve the full asset entry, including equippable group and part ids.
+    /// The asset doesn't need to be added to any token.
+    #[ink(message)]
+    fn get_asset(&self, asset_id: AssetId) -> Option<Asset>;
+
+    /// Used to retrieve the address of the base which holds the asset's parts.
+    /// # Returns
+    ///  * `None` if the asset was added without a base address
+    #[ink(message)]
+    fn get_asset_base(&self, asset_id: AssetId) -> Option<AccountId>;
+
+    /// Used to retrieve a page of the collection's asset ids.
+    /// Ids are returned in the order they were added until an asset entry is removed, the last
+    /// asset id then takes the place of the removed one.
+    /// # Arguments
+    ///  * `offset` Number of asset ids to skip
+    ///  * `limit` Maximum number of asset ids to return
+    #[ink(message)]
+    fn get_collection_asset_ids(&self, offset: u32, limit: u32) -> Vec<AssetId>;
+
+    /// Used to move asset ids of contracts deployed with the unindexed asset id list to the
+    /// indexed storage. Call repeatedly until it returns 0.
+    /// Migrated entries stay marked as added before usage was counted, see `remove_asset_entry`.
+    /// `add_asset_entry_auto` assigns ids after the highest migrated id.
+    /// # Requirements:
+    ///  * Caller must have `ADMIN` role
+    /// # Arguments
+    ///  * `limit` Maximum number of asset ids to migrate in this call
+    /// # Returns
+    ///  * u32 The number of asset ids left to migrate
+    #[ink(message)]
+    fn migrate_collection_asset_ids(&mut self, limit: u32) -> Result<u32>;
+
     /// Remove the assets for the list of token assets
+    /// The caller must own the token or be approved to manage the token's assets.
     #[ink(message)]
     fn remove_asset(&mut self, token_id: Id, asset_id: AssetId) -> Result<()>;
 
     /// Check that asset id does not already exist.
     fn ensure_asset_id_is_available(&self, asset_id: AssetId) -> Result<()>;
+
+    /// Check that no asset entry of this contract references the `PartId` of the local base.
+    /// Return `PartInUse` error if one does.
+    /// Entries added before part references were counted are counted when their ids are
+    /// migrated, and read one by one until then.
+    fn ensure_part_unreferenced(&self, part_id: PartId) -> Result<()>;
+
+    /// Clear the asset approval given by the previous owner of the token.
+    /// Meant to be called from the PSP34 `_before_token_transfer` hook, so the approval doesn't
+    /// come back if the token returns to that owner.
+    fn clear_asset_approval(&mut self, from: Option<&AccountId>, token_id: &Id);
+
+    /// Remove all pending and accepted assets of a burned token and decrease their usage, so
+    /// the asset entries can be removed once no other token uses them.
+    /// Meant to be called from the PSP34 `_before_token_transfer` hook when the token is burned.
+    fn release_token_assets(&mut self, token_id: &Id);
+}
+
+/// Trait definitions for typed assets.
+/// An optional extension of MultiAsset which stores a media type per asset entry.
+#[openbrush::trait_definition]
+pub trait MultiAssetTyped {
+    /// Used to set the media type of an asset entry.
+    /// # Requirements:
+    ///  * Caller must have `CONTRIBUTOR` role
+    ///  * `assetId` must exist.
+    /// # Arguments
+    ///  * assetId ID of the asset
+    ///  * mediaType Media type of the asset
+    ///  * thumbnailUri Uri of a preview image for the asset
+    #[ink(message)]
+    fn set_asset_type(
+        &mut self,
+        asset_id: AssetId,
+        media_type: AssetMediaType,
+        thumbnail_uri: Option<String>,
+    ) -> Result<()>;
+
+    /// Used to retrieve the media type of an asset entry.
+    /// # Returns
+    ///  * `None` if the asset has no media type set
+    #[ink(message)]
+    fn get_asset_type(&self, asset_id: AssetId) -> Option<TypedAsset>;
+
+    /// Fetch accepted assets of the token with the given media type, in the active assets order.
+    #[ink(message)]
+    fn get_accepted_assets_of_type(
+        &self,
+        token_id: Id,
+        media_type: AssetMediaType,
+    ) -> Result<Vec<AssetId>>;
+
+    /// Used to retrieve the accepted asset with the given media type and the highest priority,
+    /// e.g. the image to display for the token.
+    /// # Returns
+    ///  * `None` if the token has no accepted asset of the given media type
+    #[ink(message)]
+    fn get_top_asset_of_type(
+        &self,
+        token_id: Id,
+        media_type: AssetMediaType,
+    ) -> Result<Option<AssetId>>;
+}
+
+/// Trait definitions for MultiAsset hooks
+#[openbrush::trait_definition]
+pub trait MultiAssetHooks {
+    /// Called before a new asset entry is stored.
+    /// Fails if a part id doesn't exist in the asset's base, or if the asset has parts but no base.
+    fn _validate_asset_parts(
+        &self,
+        base_address: &Option<AccountId>,
+        part_ids: &Vec<PartId>,
+    ) -> Result<()>;
 }
 
 /// Trait definitions for MultiAsset ink events
@@ -143,7 +462,12 @@ pub trait MultiAssetEvents {
     /// * tokenId ID of the token that had a new asset accepted
     /// * assetId ID of the asset that was accepted
     /// * replacesId ID of the asset that was replaced
-    fn _emit_asset_accepted_event(&self, token_id: &Id, asset_id: &AssetId);
+    fn _emit_asset_accepted_event(
+        &self,
+        token_id: &Id,
+        asset_id: &AssetId,
+        replaces_id: &Option<AssetId>,
+    );
 
     /// Used to notify listeners that an asset object at `assetId` is rejected from token and is dropped
     /// from the pending assets array of the token.
@@ -161,5 +485,51 @@ pub trait MultiAssetEvents {
     /// Used to notify listeners that token's prioritiy array is reordered.
     /// # Arguments
     /// * tokenId ID of the token that had the asset priority array updated
-    fn _emit_asset_priority_set_event(&self, token_id: &Id, priorities: Vec<AssetId>);
+    /// * priorities New priorities of the token's active assets
+    fn _emit_asset_priority_set_event(&self, token_id: &Id, priorities: Vec<u16>);
+
+    /// Used to notify listeners that the uri of an asset entry is updated.
+    /// # Arguments
+    /// * assetId ID of the updated asset
+    fn _emit_asset_uri_set_event(&self, asset_id: &AssetId);
+
+    /// Used to notify listeners that the uri of an asset is set for a single token.
+    /// # Arguments
+    /// * tokenId ID of the token
+    /// * assetId ID of the asset
+    fn _emit_token_asset_uri_set_event(&self, token_id: &Id, asset_id: &AssetId);
+
+    /// Used to notify listeners that an asset entry can no longer be added to tokens.
+    /// # Arguments
+    /// * assetId ID of the deprecated asset
+    fn _emit_asset_deprecated_event(&self, asset_id: &AssetId);
+
+    /// Used to notify listeners that an asset entry is removed from the collection.
+    /// # Arguments
+    /// * assetId ID of the removed asset
+    fn _emit_asset_entry_removed_event(&self, asset_id: &AssetId);
+
+    /// Used to notify listeners that an account is approved to manage the token's assets.
+    /// # Arguments
+    /// * owner Owner of the token
+    /// * operator Approved account, `None` if the approval was removed
+    /// * tokenId ID of the token whose assets can be managed
+    fn _emit_approval_for_assets_event(
+        &self,
+        owner: &AccountId,
+        operator: &Option<AccountId>,
+        token_id: &Id,
+    );
+
+    /// Used to notify listeners that an operator is approved or removed for all owner's assets.
+    /// # Arguments
+    /// * owner Owner of the tokens
+    /// * operator Account added or removed as an operator
+    /// * approved Whether the operator is approved
+    fn _emit_approval_for_all_for_assets_event(
+        &self,
+        owner: &AccountId,
+        operator: &AccountId,
+        approved: bool,
+    );
 }
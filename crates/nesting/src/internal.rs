@@ -1,6 +1,9 @@
 use crate::{
-    traits::NestingEvents,
+    traits::NestingRef,
     NestingData,
+    DEFAULT_MAX_ACCEPTED_CHILDREN,
+    DEFAULT_MAX_PENDING_CHILDREN,
+    MAX_NESTING_DEPTH,
 };
 
 use rmrk_common::{
@@ -15,7 +18,10 @@ use ink_env::CallFlags;
 use ink_prelude::vec::Vec;
 
 use openbrush::{
-    contracts::psp34::extensions::enumerable::*,
+    contracts::psp34::{
+        balances::BalancesManager,
+        extensions::enumerable::*,
+    },
     traits::{
         AccountId,
         Storage,
@@ -31,137 +37,224 @@ pub trait Internal {
     fn pending(&self, parent_token_id: &Id, child_nft: &ChildNft) -> Result<()>;
 
     /// Add the child to the list of accepted children.
-    fn add_to_accepted(&mut self, parent_token_id: Id, child_nft: ChildNft);
+    fn add_to_accepted(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<()>;
 
-    /// Remove the child to the list of accepted children.
+    /// Remove the child from the list of accepted children.
     fn remove_accepted(&mut self, parent_token_id: &Id, child_nft: &ChildNft) -> Result<()>;
 
-    /// Add the child to the list of pending children.
-    fn add_to_pending(&mut self, parent_token_id: Id, child_nft: ChildNft);
+    /// Add the child to the list of pending children. `sender` receives the child on rejection.
+    fn add_to_pending(
+        &mut self,
+        parent_token_id: Id,
+        child_nft: ChildNft,
+        sender: AccountId,
+    ) -> Result<()>;
+
+    /// Remove the child from the list of pending children. Returns the recorded sender.
+    fn remove_from_pending(
+        &mut self,
+        parent_token_id: &Id,
+        child_nft: &ChildNft,
+    ) -> Result<Option<AccountId>>;
+
+    /// Move the children of the parent token from the previous storage layout.
+    fn migrate_legacy_children(&mut self, parent_token_id: &Id);
 
-    /// Remove the child to the list of pending children.
-    fn remove_from_pending(&mut self, parent_token_id: &Id, child_nft: &ChildNft) -> Result<()>;
+    /// Find the parent token holding the child, including parents not migrated yet.
+    fn find_parent_of_child(&self, child_nft: &ChildNft) -> Option<Id>;
+
+    /// Get the effective `(max_pending, max_accepted)` children limits.
+    fn children_limits(&self) -> (u32, u32);
 
     /// Check if caller is the owner of this parent token.
     fn is_caller_parent_owner(&self, caller: AccountId, parent_token_id: &Id) -> Result<()>;
 
-    /// Cross contract call to transfer child nft ownership.
-    fn transfer_child_ownership(&self, to: AccountId, child_nft: ChildNft) -> Result<()>;
+    /// Transfer child nft ownership.
+    /// Cross contract call, or internal transfer for a child of this collection.
+    fn transfer_child_ownership(&mut self, to: AccountId, child_nft: ChildNft) -> Result<()>;
+
+    /// Try to transfer child nft ownership without failing the caller.
+    /// Returns `false` if the child contract refused or reverted the transfer.
+    fn try_transfer_child_ownership(&mut self, to: AccountId, child_nft: ChildNft) -> bool;
+
+    /// Transfer a token of this collection without a cross contract call.
+    /// The token can be moved by its owner, an approved caller, or this contract when it holds
+    /// the token as a child. Callers moving a token into this contract must check it isn't
+    /// already held, see `ensure_child_not_held`.
+    fn transfer_own_token(&mut self, to: AccountId, token_id: Id) -> Result<()>;
+
+    /// Cross contract call to approve `operator` to transfer the child nft owned by this contract.
+    fn approve_child_transfer(&self, operator: AccountId, child_nft: ChildNft) -> Result<()>;
+
+    /// Cross contract call to nest the child nft into a parent token of another contract.
+    fn add_child_to_contract(
+        &self,
+        dest_contract: AccountId,
+        dest_parent_id: Id,
+        child_nft: ChildNft,
+    ) -> Result<()>;
+
+    /// Check that the child is not an ancestor of the parent token.
+    fn ensure_no_nesting_cycle(&self, parent_token_id: &Id, child_nft: &ChildNft) -> Result<()>;
+
+    /// Check that the child is not already held by this contract.
+    fn ensure_child_not_held(&self, child_nft: &ChildNft) -> Result<()>;
 }
 
 /// Implement internal helper trait for Nesting
 impl<T> Internal for T
 where
-    T: Storage<NestingData> + Storage<psp34::Data<enumerable::Balances>>,
+    T: Storage<NestingData> + Storage<psp34::Data<enumerable::Balances>> + psp34::Internal,
 {
     /// Check if child is already accepted
     default fn accepted(&self, parent_token_id: &Id, child_nft: &ChildNft) -> Result<()> {
-        if let Some(children) = self
+        if self
             .data::<NestingData>()
             .accepted_children
-            .get(parent_token_id)
+            .contains(parent_token_id, child_nft)
         {
-            if children.contains(child_nft) {
-                return Err(RmrkError::AlreadyAddedChild.into())
-            }
+            return Err(RmrkError::AlreadyAddedChild.into())
         }
         Ok(())
     }
 
     /// Check if child is already pending
     default fn pending(&self, parent_token_id: &Id, child_nft: &ChildNft) -> Result<()> {
-        if let Some(children) = self
+        if self
             .data::<NestingData>()
             .pending_children
-            .get(parent_token_id)
+            .contains(parent_token_id, child_nft)
         {
-            if children.contains(child_nft) {
-                return Err(RmrkError::AddingPendingChild.into())
-            }
+            return Err(RmrkError::AddingPendingChild.into())
         }
         Ok(())
     }
 
     /// Add the child to the list of accepted children
-    default fn add_to_accepted(&mut self, parent_token_id: Id, child_nft: ChildNft) {
-        let mut child_nfts = self
-            .data::<NestingData>()
-            .accepted_children
-            .get(&parent_token_id)
-            .unwrap_or(Vec::new());
-        if !child_nfts.contains(&child_nft) {
-            child_nfts.push(child_nft.clone());
+    default fn add_to_accepted(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<()> {
+        let (_, max_accepted) = self.children_limits();
+        let accepted_children = &self.data::<NestingData>().accepted_children;
+        if !accepted_children.contains(&parent_token_id, &child_nft) {
+            if accepted_children.len(&parent_token_id) >= max_accepted {
+                return Err(RmrkError::MaxAcceptedChildrenReached.into())
+            }
             self.data::<NestingData>()
                 .accepted_children
-                .insert(&parent_token_id, &child_nfts);
-            self._emit_child_accepted_event(&parent_token_id, &child_nft.0, &child_nft.1);
+                .push(&parent_token_id, &child_nft);
+            self.data::<NestingData>()
+                .child_parents
+                .insert(&child_nft, &parent_token_id);
         }
+        Ok(())
     }
 
-    /// Remove the child to the list of accepted children
+    /// Remove the child from the list of accepted children
     default fn remove_accepted(
         &mut self,
         parent_token_id: &Id,
         child_nft: &ChildNft,
     ) -> Result<()> {
-        let mut child_nfts = self
-            .data::<NestingData>()
-            .accepted_children
-            .get(&parent_token_id)
-            .ok_or(RmrkError::InvalidParentId)?;
-
-        let index = child_nfts
-            .iter()
-            .position(|x| x == child_nft)
-            .ok_or(RmrkError::ChildNotFound)?;
-        child_nfts.remove(index);
-
         self.data::<NestingData>()
             .accepted_children
-            .insert(&parent_token_id, &child_nfts);
+            .remove(parent_token_id, child_nft)?;
+        self.data::<NestingData>().child_parents.remove(child_nft);
 
-        self._emit_child_removed_event(&parent_token_id, &child_nft.0, &child_nft.1);
         Ok(())
     }
 
     /// Add the child to the list of pending children
-    default fn add_to_pending(&mut self, parent_token_id: Id, child_nft: ChildNft) {
-        let mut child_nfts = self
-            .data::<NestingData>()
-            .pending_children
-            .get(&parent_token_id)
-            .unwrap_or(Vec::new());
-        if !child_nfts.contains(&child_nft) {
-            child_nfts.push(child_nft);
-            self.data::<NestingData>()
-                .pending_children
-                .insert(&parent_token_id, &child_nfts);
+    default fn add_to_pending(
+        &mut self,
+        parent_token_id: Id,
+        child_nft: ChildNft,
+        sender: AccountId,
+    ) -> Result<()> {
+        let (max_pending, _) = self.children_limits();
+        let pending_children = &self.data::<NestingData>().pending_children;
+        if !pending_children.contains(&parent_token_id, &child_nft) {
+            if pending_children.len(&parent_token_id) >= max_pending {
+                return Err(RmrkError::MaxPendingChildrenReached.into())
+            }
+            let data = self.data::<NestingData>();
+            data.pending_children.push(&parent_token_id, &child_nft);
+            data.child_parents.insert(&child_nft, &parent_token_id);
+            data.pending_child_senders.insert(&child_nft, &sender);
         }
+        Ok(())
     }
 
-    /// Remove the child to the list of pending children
+    /// Remove the child from the list of pending children
     default fn remove_from_pending(
         &mut self,
         parent_token_id: &Id,
         child_nft: &ChildNft,
-    ) -> Result<()> {
-        let mut child_nfts = self
-            .data::<NestingData>()
-            .pending_children
-            .get(&parent_token_id)
-            .ok_or(RmrkError::InvalidParentId)?;
+    ) -> Result<Option<AccountId>> {
+        let data = self.data::<NestingData>();
+        data.pending_children.remove(parent_token_id, child_nft)?;
+        data.child_parents.remove(child_nft);
+        let sender = data.pending_child_senders.get(child_nft);
+        data.pending_child_senders.remove(child_nft);
 
-        let index = child_nfts
-            .iter()
-            .position(|x| x == child_nft)
-            .ok_or(RmrkError::ChildNotFound)?;
-        child_nfts.remove(index);
+        Ok(sender)
+    }
 
-        self.data::<NestingData>()
-            .pending_children
-            .insert(&parent_token_id, &child_nfts);
+    /// Move the children of the parent token from the previous storage layout
+    default fn migrate_legacy_children(&mut self, parent_token_id: &Id) {
+        let data = self.data::<NestingData>();
+        if let Some(child_nfts) = data.legacy_pending_children.get(parent_token_id) {
+            for child_nft in child_nfts.iter() {
+                if !data.pending_children.contains(parent_token_id, child_nft) {
+                    data.pending_children.push(parent_token_id, child_nft);
+                    data.child_parents.insert(child_nft, parent_token_id);
+                }
+            }
+            data.legacy_pending_children.remove(parent_token_id);
+        }
+        if let Some(child_nfts) = data.legacy_accepted_children.get(parent_token_id) {
+            for child_nft in child_nfts.iter() {
+                if !data.accepted_children.contains(parent_token_id, child_nft) {
+                    data.accepted_children.push(parent_token_id, child_nft);
+                    data.child_parents.insert(child_nft, parent_token_id);
+                }
+            }
+            data.legacy_accepted_children.remove(parent_token_id);
+        }
+    }
 
-        Ok(())
+    /// Find the parent token holding the child, including parents not migrated yet.
+    /// Children in the previous storage layout are only found by going through every token.
+    default fn find_parent_of_child(&self, child_nft: &ChildNft) -> Option<Id> {
+        let data = self.data::<NestingData>();
+        if let Some(parent_token_id) = data.child_parents.get(child_nft) {
+            return Some(parent_token_id)
+        }
+        (0u128..)
+            .map_while(|index| self.token_by_index(index).ok())
+            .find(|parent_token_id| {
+                data.legacy_pending_children
+                    .get(parent_token_id)
+                    .unwrap_or_default()
+                    .contains(child_nft)
+                    || data
+                        .legacy_accepted_children
+                        .get(parent_token_id)
+                        .unwrap_or_default()
+                        .contains(child_nft)
+            })
+    }
+
+    /// Get the effective `(max_pending, max_accepted)` children limits
+    default fn children_limits(&self) -> (u32, u32) {
+        let data = self.data::<NestingData>();
+        let max_pending = match data.max_pending_children {
+            0 => DEFAULT_MAX_PENDING_CHILDREN,
+            limit => limit,
+        };
+        let max_accepted = match data.max_accepted_children {
+            0 => DEFAULT_MAX_ACCEPTED_CHILDREN,
+            limit => limit,
+        };
+        (max_pending, max_accepted)
     }
 
     /// Check if caller is the owner of this parent token
@@ -181,8 +274,17 @@ where
         Ok(())
     }
 
-    /// Cross contract call to transfer child nft ownership
-    default fn transfer_child_ownership(&self, to: AccountId, child_nft: ChildNft) -> Result<()> {
+    /// Transfer child nft ownership
+    default fn transfer_child_ownership(
+        &mut self,
+        to: AccountId,
+        child_nft: ChildNft,
+    ) -> Result<()> {
+        // A cross contract call into this contract would be reentrant
+        if child_nft.0 == Self::env().account_id() {
+            return self.transfer_own_token(to, child_nft.1)
+        }
+
         // TODO check child collection is approved by this (parent) collection
         // let collection = self.get_collection(child_nft.0)
         //      .ok_or(RmrkError::ChildContractNotApproved)?;
@@ -194,4 +296,158 @@ where
 
         Ok(())
     }
+
+    /// Try to transfer child nft ownership without failing the caller
+    default fn try_transfer_child_ownership(&mut self, to: AccountId, child_nft: ChildNft) -> bool {
+        if child_nft.0 == Self::env().account_id() {
+            return self.transfer_own_token(to, child_nft.1).is_ok()
+        }
+
+        // A reverted call is returned as an error instead of trapping this contract
+        matches!(
+            PSP34Ref::transfer_builder(&child_nft.0, to, child_nft.1, Vec::new())
+                .call_flags(CallFlags::default().set_allow_reentry(true))
+                .fire(),
+            Ok(Ok(()))
+        )
+    }
+
+    /// Transfer a token of this collection without a cross contract call
+    default fn transfer_own_token(&mut self, to: AccountId, token_id: Id) -> Result<()> {
+        let owner = self
+            .data::<psp34::Data<enumerable::Balances>>()
+            .owner_of(token_id.clone())
+            .ok_or(PSP34Error::TokenNotExists)?;
+        let caller = Self::env().caller();
+        if owner != Self::env().account_id()
+            && owner != caller
+            && !self.data::<psp34::Data<enumerable::Balances>>().allowance(
+                owner,
+                caller,
+                Some(token_id.clone()),
+            )
+        {
+            return Err(PSP34Error::NotApproved.into())
+        }
+
+        self._before_token_transfer(Some(&owner), Some(&to), &token_id)?;
+        let psp34 = self.data::<psp34::Data<enumerable::Balances>>();
+        psp34.balances.decrease_balance(&owner, &token_id, false);
+        psp34.balances.increase_balance(&to, &token_id, false);
+        psp34.token_owner.insert(&token_id, &to);
+        self._after_token_transfer(Some(&owner), Some(&to), &token_id)?;
+        self._emit_transfer_event(Some(owner), Some(to), token_id);
+
+        Ok(())
+    }
+
+    /// Cross contract call to approve `operator` to transfer the child nft owned by this contract
+    default fn approve_child_transfer(
+        &self,
+        operator: AccountId,
+        child_nft: ChildNft,
+    ) -> Result<()> {
+        PSP34Ref::approve_builder(&child_nft.0, operator, Some(child_nft.1), true)
+            .call_flags(CallFlags::default().set_allow_reentry(true))
+            .fire()
+            .unwrap()?;
+
+        Ok(())
+    }
+
+    /// Cross contract call to nest the child nft into a parent token of another contract.
+    /// The destination contract may query this contract while checking for nesting cycles.
+    default fn add_child_to_contract(
+        &self,
+        dest_contract: AccountId,
+        dest_parent_id: Id,
+        child_nft: ChildNft,
+    ) -> Result<()> {
+        NestingRef::add_child_builder(&dest_contract, dest_parent_id, child_nft)
+            .call_flags(CallFlags::default().set_allow_reentry(true))
+            .fire()
+            .unwrap()?;
+
+        Ok(())
+    }
+
+    /// Walk up the ancestor chain of the parent token and fail if the child is found.
+    /// Ancestors in other contracts are resolved with cross contract queries.
+    default fn ensure_no_nesting_cycle(
+        &self,
+        parent_token_id: &Id,
+        child_nft: &ChildNft,
+    ) -> Result<()> {
+        let this_contract = Self::env().account_id();
+        let mut ancestor: ChildNft = (this_contract, parent_token_id.clone());
+
+        for _ in 0..MAX_NESTING_DEPTH {
+            if ancestor == *child_nft {
+                return Err(RmrkError::NestingCycle.into())
+            }
+
+            let owner = if ancestor.0 == this_contract {
+                self.data::<psp34::Data<enumerable::Balances>>()
+                    .owner_of(ancestor.1.clone())
+            } else {
+                PSP34Ref::owner_of_builder(&ancestor.0, ancestor.1.clone())
+                    .fire()
+                    .unwrap_or(None)
+            };
+            let owner = match owner {
+                Some(owner) => owner,
+                None => return Ok(()),
+            };
+
+            // Only a contract can hold a nested token
+            let parent = if owner == this_contract {
+                self.find_parent_of_child(&ancestor)
+            } else if Self::env().is_contract(&owner) {
+                NestingRef::get_parent_of_child_builder(&owner, ancestor.clone())
+                    .fire()
+                    .unwrap_or(None)
+            } else {
+                None
+            };
+            match parent {
+                Some(parent_id) => ancestor = (owner, parent_id),
+                None => return Ok(()),
+            }
+        }
+
+        Err(RmrkError::NestingTooDeep.into())
+    }
+
+    /// Fail with `ChildIsNested` if the child has a parent token in this contract, or is owned by
+    /// this contract. Every nested child is owned by this contract, including children in the
+    /// previous storage layout which have no `child_parents` entry yet.
+    /// The child's collection may hand over its own token before adding it, as `send_child_to`
+    /// does for a child of that collection.
+    default fn ensure_child_not_held(&self, child_nft: &ChildNft) -> Result<()> {
+        if self
+            .data::<NestingData>()
+            .child_parents
+            .get(child_nft)
+            .is_some()
+        {
+            return Err(RmrkError::ChildIsNested.into())
+        }
+
+        let this_contract = Self::env().account_id();
+        if Self::env().caller() == child_nft.0 {
+            return Ok(())
+        }
+        let owner = if child_nft.0 == this_contract {
+            self.data::<psp34::Data<enumerable::Balances>>()
+                .owner_of(child_nft.1.clone())
+        } else {
+            PSP34Ref::owner_of_builder(&child_nft.0, child_nft.1.clone())
+                .fire()
+                .map_err(|_| PSP34Error::TokenNotExists)?
+        };
+        if owner == Some(this_contract) {
+            return Err(RmrkError::ChildIsNested.into())
+        }
+        Ok(())
+    }
 }
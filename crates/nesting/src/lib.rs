@@ -7,7 +7,14 @@ pub mod internal;
 pub mod traits;
 
 use rmrk_common::{
-    errors::Result,
+    errors::{
+        Result,
+        RmrkError,
+    },
+    roles::{
+        ADMIN,
+        CONTRIBUTOR,
+    },
     types::*,
     utils::Utils,
 };
@@ -17,13 +24,29 @@ use internal::Internal;
 use traits::{
     Nesting,
     NestingEvents,
+    NestingHooks,
+    NestingRef,
 };
 
-use ink_prelude::vec::Vec;
-use ink_storage::Mapping;
+use ink_env::CallFlags;
+use ink_prelude::{
+    string::String,
+    vec::Vec,
+};
+use ink_storage::{
+    traits::{
+        SpreadAllocate,
+        SpreadLayout,
+    },
+    Mapping,
+};
 
 use openbrush::{
-    contracts::psp34::extensions::enumerable::*,
+    contracts::{
+        access_control::*,
+        psp34::extensions::enumerable::*,
+    },
+    modifiers,
     traits::{
         AccountId,
         Storage,
@@ -32,18 +55,125 @@ use openbrush::{
 
 pub const STORAGE_NESTING_KEY: u32 = openbrush::storage_unique_key!(NestingData);
 
+/// Maximum number of ancestors walked when checking for nesting cycles.
+pub const MAX_NESTING_DEPTH: u32 = 16;
+
+/// Maximum number of pending children per parent token, used until configured.
+pub const DEFAULT_MAX_PENDING_CHILDREN: u32 = 128;
+
+/// Maximum number of accepted children per parent token, used until configured.
+pub const DEFAULT_MAX_ACCEPTED_CHILDREN: u32 = 128;
+
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_NESTING_KEY)]
 pub struct NestingData {
-    pub pending_children: Mapping<Id, Vec<ChildNft>>,
-    pub accepted_children: Mapping<Id, Vec<ChildNft>>,
+    /// Pending children in the previous storage layout. Emptied by `migrate_children`.
+    pub legacy_pending_children: Mapping<Id, Vec<ChildNft>>,
+    /// Accepted children in the previous storage layout. Emptied by `migrate_children`.
+    pub legacy_accepted_children: Mapping<Id, Vec<ChildNft>>,
+    /// Parent token holding each pending or accepted child.
+    pub child_parents: Mapping<ChildNft, Id>,
+    /// Maximum number of pending children per parent token. Zero means the default.
+    pub max_pending_children: u32,
+    /// Maximum number of accepted children per parent token. Zero means the default.
+    pub max_accepted_children: u32,
+    /// Pending children per parent token.
+    pub pending_children: ChildrenList,
+    /// Accepted children per parent token.
+    pub accepted_children: ChildrenList,
+    /// Account which sent each pending child. Receives the child back on rejection.
+    pub pending_child_senders: Mapping<ChildNft, AccountId>,
+}
+
+/// List of children per parent token, stored as a count and one entry per child.
+/// Adding, removing and looking up a child don't load the rest of the list.
+#[derive(Default, Debug, SpreadLayout, SpreadAllocate)]
+#[cfg_attr(feature = "std", derive(ink_storage::traits::StorageLayout))]
+pub struct ChildrenList {
+    /// Number of children per parent token.
+    pub count: Mapping<Id, u32>,
+    /// Child stored at each index of the parent token's list.
+    pub children: Mapping<(Id, u32), ChildNft>,
+    /// Index of each child in the parent token's list.
+    pub index: Mapping<(Id, ChildNft), u32>,
+}
+
+impl ChildrenList {
+    /// Number of children of the parent token.
+    pub fn len(&self, parent_token_id: &Id) -> u32 {
+        self.count.get(parent_token_id).unwrap_or(0)
+    }
+
+    /// Check if the child is in the parent token's list.
+    pub fn contains(&self, parent_token_id: &Id, child_nft: &ChildNft) -> bool {
+        self.index.get((parent_token_id, child_nft)).is_some()
+    }
+
+    /// Child stored at `index` of the parent token's list.
+    pub fn get(&self, parent_token_id: &Id, index: u32) -> Option<ChildNft> {
+        self.children.get((parent_token_id, index))
+    }
+
+    /// All children of the parent token.
+    pub fn list(&self, parent_token_id: &Id) -> Vec<ChildNft> {
+        (0..self.len(parent_token_id))
+            .filter_map(|index| self.get(parent_token_id, index))
+            .collect()
+    }
+
+    /// Append the child to the parent token's list.
+    pub fn push(&mut self, parent_token_id: &Id, child_nft: &ChildNft) {
+        let index = self.len(parent_token_id);
+        self.children.insert((parent_token_id, index), child_nft);
+        self.index.insert((parent_token_id, child_nft), &index);
+        self.count.insert(parent_token_id, &(index + 1));
+    }
+
+    /// Remove the child from the parent token's list.
+    /// The last child takes the place of the removed one.
+    pub fn remove(&mut self, parent_token_id: &Id, child_nft: &ChildNft) -> Result<()> {
+        let index = self
+            .index
+            .get((parent_token_id, child_nft))
+            .ok_or(RmrkError::ChildNotFound)?;
+        let last_index = self.len(parent_token_id) - 1;
+
+        if index != last_index {
+            let last_child = self
+                .get(parent_token_id, last_index)
+                .ok_or(RmrkError::ChildNotFound)?;
+            self.children.insert((parent_token_id, index), &last_child);
+            self.index.insert((parent_token_id, &last_child), &index);
+        }
+        self.children.remove((parent_token_id, last_index));
+        self.index.remove((parent_token_id, child_nft));
+        self.count.insert(parent_token_id, &last_index);
+
+        Ok(())
+    }
+
+    /// Remove all children of the parent token. Returns the removed children.
+    pub fn clear(&mut self, parent_token_id: &Id) -> Vec<ChildNft> {
+        let child_nfts = self.list(parent_token_id);
+        for (index, child_nft) in child_nfts.iter().enumerate() {
+            self.children.remove((parent_token_id, index as u32));
+            self.index.remove((parent_token_id, child_nft));
+        }
+        self.count.remove(parent_token_id);
+
+        child_nfts
+    }
 }
 
 impl<T> Nesting for T
 where
-    T: Storage<NestingData> + Storage<psp34::Data<enumerable::Balances>> + Utils,
+    T: Storage<NestingData>
+        + Storage<psp34::Data<enumerable::Balances>>
+        + Storage<access_control::Data>
+        + psp34::Internal
+        + Utils,
 {
-    /// Add a child NFT (from different collection) to the NFT in this collection
+    /// Add a child NFT (from any collection, including this one) to the NFT in this collection
     /// The status of the added child is `Pending` if caller is not owner of child NFT
     /// The status of the added child is `Accepted` if caller is is owner of child NFT
     /// The caller needs not to be the owner of the to_parent_token_id, but
@@ -55,6 +185,8 @@ where
     /// * `to_parent_token_id` must exist.
     /// * `child_token_id` must exist.
     /// * There cannot be two identical children.
+    /// * `to_parent_token_id` must not be nested (directly or indirectly) under the child.
+    /// * The child must not be nested in, or owned by, this contract.
     ///
     /// # Arguments:
     /// * `to_parent_token_id`: is the tokenId of the parent NFT. The receiver of child.
@@ -62,24 +194,28 @@ where
     ///
     /// # Result:
     /// Ownership of child NFT will be transferred to this contract (cross contract call)
-    /// On success emitts `RmrkEvent::ChildAdded`
-    /// On success emitts `RmrkEvent::ChildAccepted` - only if caller is already owner of child NFT
+    /// On success emitts `RmrkEvent::ChildAdded` - if the child is pending
+    /// On success emitts `RmrkEvent::ChildAccepted` - instead, if the caller owns the parent
     default fn add_child(&mut self, to_parent_token_id: Id, child_nft: ChildNft) -> Result<()> {
         let parent_owner = self.ensure_exists_and_get_owner(&to_parent_token_id)?;
         self.accepted(&to_parent_token_id, &child_nft)?;
         self.pending(&to_parent_token_id, &child_nft)?;
+        self.ensure_no_nesting_cycle(&to_parent_token_id, &child_nft)?;
+        // A child held by this contract would be transferred by this contract itself
+        self.ensure_child_not_held(&child_nft)?;
 
         // Transfer child ownership to this contract.
         // This transfer call will fail if caller is not child owner
         self.transfer_child_ownership(Self::env().account_id(), child_nft.clone())?;
 
-        // Insert child nft and emit event
-        self._emit_added_child_event(&to_parent_token_id, &child_nft.0, &child_nft.1);
+        // Insert child nft and emit one event for the new state
         let caller = Self::env().caller();
         if caller == parent_owner {
-            self.add_to_accepted(to_parent_token_id, child_nft);
+            self.add_to_accepted(to_parent_token_id.clone(), child_nft.clone())?;
+            self._emit_child_accepted_event(&to_parent_token_id, &child_nft.0, &child_nft.1);
         } else {
-            self.add_to_pending(to_parent_token_id, child_nft);
+            self.add_to_pending(to_parent_token_id.clone(), child_nft.clone(), caller)?;
+            self._emit_added_child_event(&to_parent_token_id, &child_nft.0, &child_nft.1);
         }
 
         Ok(())
@@ -100,17 +236,43 @@ where
     /// Ownership of child NFT will be transferred to parent NFT owner (cross contract call)
     /// On success emitts `RmrkEvent::ChildRemoved`
     default fn remove_child(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<()> {
+        let token_owner = self.ensure_exists_and_get_owner(&parent_token_id)?;
+        self.remove_child_to(parent_token_id, child_nft, token_owner)
+    }
+
+    /// Remove a child NFT (from different collection) from token_id in this collection
+    /// and transfer it to any account
+    ///
+    /// # Requirements:
+    /// * Caller must be the owner of `parent_token_id`
+    /// * The status of the child is `Accepted`
+    ///
+    /// # Arguments:
+    /// * `parent_token_id`: is the tokenId of the parent NFT.
+    /// * `child_nft`: (collection_id, token_id) of the child instance.
+    /// * `to`: account receiving the child NFT
+    ///
+    /// # Result:
+    /// Ownership of child NFT will be transferred to `to` (cross contract call)
+    /// On success emitts `RmrkEvent::ChildRemoved`
+    default fn remove_child_to(
+        &mut self,
+        parent_token_id: Id,
+        child_nft: ChildNft,
+        to: AccountId,
+    ) -> Result<()> {
         self.ensure_exists_and_get_owner(&parent_token_id)?;
         let caller = Self::env().caller();
         self.is_caller_parent_owner(caller, &parent_token_id)?;
 
         // Remove child nft
+        self._before_child_removed(&parent_token_id, &child_nft)?;
         self.remove_accepted(&parent_token_id, &child_nft)?;
+        self._emit_child_removed_event(&parent_token_id, &child_nft.0, &child_nft.1, &to);
 
-        // Transfer child ownership from this contract to parent_token owner.
+        // Transfer child ownership from this contract to the recipient.
         // This call will fail if this contract is not child owner
-        let token_owner = self.ensure_exists_and_get_owner(&parent_token_id)?;
-        self.transfer_child_ownership(token_owner, child_nft)?;
+        self.transfer_child_ownership(to, child_nft)?;
 
         Ok(())
     }
@@ -134,7 +296,8 @@ where
         self.accepted(&parent_token_id, &child_nft)?;
 
         self.remove_from_pending(&parent_token_id, &child_nft)?;
-        self.add_to_accepted(parent_token_id, child_nft);
+        self.add_to_accepted(parent_token_id.clone(), child_nft.clone())?;
+        self._emit_child_accepted_event(&parent_token_id, &child_nft.0, &child_nft.1);
 
         Ok(())
     }
@@ -149,7 +312,9 @@ where
     /// * `child_nft`: (collection_id, token_id) of the child instance.
     ///
     /// # Result:
-    /// Child Nft is removed from pending
+    /// Child Nft is removed from pending and returned to its sender (cross contract call)
+    /// If the child contract refuses the return transfer, the child stays owned by this contract
+    /// and can be recovered with `reclaim_child`.
     /// On success emitts `RmrkEvent::ChildRejected`
     default fn reject_child(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<()> {
         self.ensure_exists_and_get_owner(&parent_token_id)?;
@@ -157,16 +322,54 @@ where
         self.is_caller_parent_owner(caller, &parent_token_id)?;
         self.accepted(&parent_token_id, &child_nft)?;
 
-        self.remove_from_pending(&parent_token_id, &child_nft)?;
+        let sender = self.remove_from_pending(&parent_token_id, &child_nft)?;
         self._emit_child_rejected_event(&parent_token_id, &child_nft.0, &child_nft.1);
+        if let Some(sender) = sender {
+            self.try_transfer_child_ownership(sender, child_nft);
+        }
 
         Ok(())
     }
 
+    /// Reject all pending children of the parent token
+    ///
+    /// # Arguments:
+    /// * `parent_token_id`: is the tokenId of the parent NFT.
+    ///
+    /// # Result:
+    /// All pending children are removed and returned to their senders (cross contract calls).
+    /// Children whose return transfer fails stay owned by this contract, see `reclaim_child`.
+    /// Returns the number of rejected children.
+    /// On success emitts `RmrkEvent::ChildRejected` for each child
+    default fn reject_all_pending(&mut self, parent_token_id: Id) -> Result<u64> {
+        self.ensure_exists_and_get_owner(&parent_token_id)?;
+        let caller = Self::env().caller();
+        self.is_caller_parent_owner(caller, &parent_token_id)?;
+
+        let child_nfts = self
+            .data::<NestingData>()
+            .pending_children
+            .clear(&parent_token_id);
+        for child_nft in child_nfts.iter() {
+            let data = self.data::<NestingData>();
+            data.child_parents.remove(child_nft);
+            let sender = data.pending_child_senders.get(child_nft);
+            data.pending_child_senders.remove(child_nft);
+
+            self._emit_child_rejected_event(&parent_token_id, &child_nft.0, &child_nft.1);
+            if let Some(sender) = sender {
+                self.try_transfer_child_ownership(sender, child_nft.clone());
+            }
+        }
+
+        Ok(child_nfts.len() as u64)
+    }
+
     /// Transfer the child NFT from one parent to another (in this collection)
     ///
     /// # Requirements:
     /// * The status of the child is `Accepted`
+    /// * `new_parent` must not be nested (directly or indirectly) under the child.
     ///
     /// # Arguments:
     /// * `current_parent`: current parent tokenId which holds child nft
@@ -174,9 +377,9 @@ where
     /// * `child_nft`: (collection_id, token_id) of the child instance.
     ///
     /// # Result:
-    /// Ownership of child NFT will be transferred to this contract (cross contract call)
-    /// On success emitts `RmrkEvent::ChildAdded`
-    /// On success emitts `RmrkEvent::ChildAccepted` - only if caller is already owner of child NFT
+    /// Child NFT stays owned by this contract
+    /// On success emitts `RmrkEvent::ChildTransferred`
+    /// The child is pending on the new parent unless both parents have the same owner
     default fn transfer_child(
         &mut self,
         current_parent: Id,
@@ -185,14 +388,76 @@ where
     ) -> Result<()> {
         let current_parent_owner = self.ensure_exists_and_get_owner(&current_parent)?;
         let new_parent_owner = self.ensure_exists_and_get_owner(&new_parent)?;
+        self.ensure_no_nesting_cycle(&new_parent, &child_nft)?;
+        self._before_child_removed(&current_parent, &child_nft)?;
         self.remove_accepted(&current_parent, &child_nft)?;
 
-        self._emit_added_child_event(&new_parent, &child_nft.0, &child_nft.1);
-        if current_parent_owner == new_parent_owner {
-            self.add_to_accepted(new_parent, child_nft);
+        let pending = current_parent_owner != new_parent_owner;
+        if pending {
+            self.add_to_pending(new_parent.clone(), child_nft.clone(), current_parent_owner)?;
         } else {
-            self.add_to_pending(new_parent, child_nft);
+            self.add_to_accepted(new_parent.clone(), child_nft.clone())?;
         }
+        self._emit_child_transferred_event(
+            &current_parent,
+            &new_parent,
+            &child_nft.0,
+            &child_nft.1,
+            pending,
+        );
+
+        Ok(())
+    }
+
+    /// Send the child NFT from the parent token to a parent token of another RMRK contract
+    ///
+    /// # Requirements:
+    /// * Caller must be the owner of `parent_token_id`
+    /// * The status of the child is `Accepted`
+    ///
+    /// # Arguments:
+    /// * `parent_token_id`: current parent tokenId which holds child nft
+    /// * `child_nft`: (collection_id, token_id) of the child instance.
+    /// * `dest_contract`: address of the RMRK contract holding the new parent
+    /// * `dest_parent_id`: tokenId of the new parent in `dest_contract`
+    ///
+    /// # Result:
+    /// Ownership of child NFT will be transferred to `dest_contract` (cross contract call)
+    /// A child rejected by the destination returns to this contract untracked, see `reclaim_child`
+    /// On success emitts `RmrkEvent::ChildRemoved`
+    default fn send_child_to(
+        &mut self,
+        parent_token_id: Id,
+        child_nft: ChildNft,
+        dest_contract: AccountId,
+        dest_parent_id: Id,
+    ) -> Result<()> {
+        self.ensure_exists_and_get_owner(&parent_token_id)?;
+        let caller = Self::env().caller();
+        self.is_caller_parent_owner(caller, &parent_token_id)?;
+
+        // Moving within this collection doesn't need cross contract calls
+        if dest_contract == Self::env().account_id() {
+            return self.transfer_child(parent_token_id, dest_parent_id, child_nft)
+        }
+
+        self._before_child_removed(&parent_token_id, &child_nft)?;
+        self.remove_accepted(&parent_token_id, &child_nft)?;
+        self._emit_child_removed_event(
+            &parent_token_id,
+            &child_nft.0,
+            &child_nft.1,
+            &dest_contract,
+        );
+
+        // Let the destination contract take the child from this contract.
+        // A child of this collection is handed over directly, approving it would be reentrant
+        if child_nft.0 == Self::env().account_id() {
+            self.transfer_own_token(dest_contract, child_nft.1.clone())?;
+        } else {
+            self.approve_child_transfer(dest_contract, child_nft.clone())?;
+        }
+        self.add_child_to_contract(dest_contract, dest_parent_id, child_nft)?;
 
         Ok(())
     }
@@ -205,29 +470,138 @@ where
     /// Returns the tupple of `(accepted_children, pending_children)` count
     fn children_balance(&self, parent_token_id: Id) -> Result<(u64, u64)> {
         self.ensure_exists_and_get_owner(&parent_token_id)?;
-        let parents_with_accepted_children = match self
+        let parents_with_accepted_children = self
             .data::<NestingData>()
             .accepted_children
-            .get(&parent_token_id)
-        {
-            Some(children) => children.len() as u64,
-            None => 0,
-        };
-
-        let parents_with_pending_children = match self
+            .len(&parent_token_id) as u64;
+        let parents_with_pending_children = self
             .data::<NestingData>()
             .pending_children
-            .get(&parent_token_id)
-        {
-            Some(children) => children.len() as u64,
-            None => 0,
-        };
+            .len(&parent_token_id) as u64;
 
         Ok((
             parents_with_accepted_children,
             parents_with_pending_children,
         ))
     }
+
+    /// Read the parent token holding the child NFT
+    /// # Arguments:
+    /// * `child_nft`: (collection_id, token_id) of the child instance.
+    ///
+    /// # Result:
+    /// Returns the parent tokenId if the child is pending or accepted on any token
+    default fn get_parent_of_child(&self, child_nft: ChildNft) -> Option<Id> {
+        self.data::<NestingData>().child_parents.get(&child_nft)
+    }
+
+    /// Check if the child NFT is pending or accepted on any token of this contract
+    /// # Arguments:
+    /// * `child_nft`: (collection_id, token_id) of the child instance.
+    default fn is_child_of(&self, child_nft: ChildNft) -> bool {
+        self.data::<NestingData>()
+            .child_parents
+            .get(&child_nft)
+            .is_some()
+    }
+
+    /// Set the maximum number of pending and accepted children per parent token
+    /// Zero restores the default limit
+    #[modifiers(only_role(CONTRIBUTOR))]
+    default fn set_children_limits(&mut self, max_pending: u32, max_accepted: u32) -> Result<()> {
+        self.data::<NestingData>().max_pending_children = max_pending;
+        self.data::<NestingData>().max_accepted_children = max_accepted;
+        Ok(())
+    }
+
+    /// Read the maximum number of children per parent token
+    ///
+    /// # Result:
+    /// Returns the tupple of `(max_pending_children, max_accepted_children)`
+    default fn get_children_limits(&self) -> (u32, u32) {
+        self.children_limits()
+    }
+
+    /// Transfer a child NFT owned by this contract, but not nested in any parent token
+    ///
+    /// # Requirements:
+    /// * Caller must have `ADMIN` role
+    /// * The child is neither pending nor accepted on any parent token, including parents which
+    ///   are not migrated yet
+    ///
+    /// # Result:
+    /// Ownership of child NFT will be transferred to `to` (cross contract call)
+    #[modifiers(only_role(ADMIN))]
+    default fn reclaim_child(&mut self, child_nft: ChildNft, to: AccountId) -> Result<()> {
+        if self.find_parent_of_child(&child_nft).is_some() {
+            return Err(RmrkError::ChildIsNested.into())
+        }
+        self.transfer_child_ownership(to, child_nft)
+    }
+
+    /// Move children of the given parent tokens from the previous storage layout
+    #[modifiers(only_role(ADMIN))]
+    default fn migrate_children(&mut self, parent_token_ids: Vec<Id>) -> Result<()> {
+        for parent_token_id in parent_token_ids {
+            self.migrate_legacy_children(&parent_token_id);
+        }
+        Ok(())
+    }
+
+    /// Read the parent tokens which still hold children in the previous storage layout
+    default fn get_unmigrated_parents(&self, offset: u32, limit: u32) -> Vec<Id> {
+        let data = self.data::<NestingData>();
+        (offset..offset.saturating_add(limit))
+            .map_while(|index| self.token_by_index(u128::from(index)).ok())
+            .filter(|parent_token_id| {
+                data.legacy_pending_children.get(parent_token_id).is_some()
+                    || data.legacy_accepted_children.get(parent_token_id).is_some()
+            })
+            .collect()
+    }
+}
+
+/// Hook trait for Nesting
+impl<T> NestingHooks for T
+where
+    T: Storage<NestingData> + Storage<psp34::Data<enumerable::Balances>>,
+{
+    /// Called before an accepted child leaves the parent token
+    default fn _before_child_removed(
+        &mut self,
+        _parent_token_id: &Id,
+        _child_nft: &ChildNft,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// Ensure that a nested token of this collection is only moved by its parent contract.
+    /// Tokens owned by accounts, or nested in this collection, are not restricted.
+    default fn _ensure_transfer_allowed_by_parent(
+        &self,
+        from: Option<&AccountId>,
+        token_id: &Id,
+    ) -> Result<()> {
+        let from = match from {
+            Some(from) => *from,
+            None => return Ok(()),
+        };
+        let this_contract = Self::env().account_id();
+        if from == this_contract || from == Self::env().caller() || !Self::env().is_contract(&from)
+        {
+            return Ok(())
+        }
+
+        // Fail closed, an owner which can't be asked may hold the token as a child
+        let is_child = NestingRef::is_child_of_builder(&from, (this_contract, token_id.clone()))
+            .call_flags(CallFlags::default().set_allow_reentry(true))
+            .fire()
+            .map_err(|_| PSP34Error::Custom(String::from("ParentCheckFailed").into()))?;
+        if is_child {
+            return Err(RmrkError::ChildIsNested.into())
+        }
+        Ok(())
+    }
 }
 
 /// Event trait for Nesting
@@ -258,6 +632,7 @@ where
         _parent: &Id,
         _child_collection_address: &AccountId,
         _child_token_id: &Id,
+        _to: &AccountId,
     ) {
     }
 
@@ -269,4 +644,15 @@ where
         _child_token_id: &Id,
     ) {
     }
+
+    /// Emit ChildTransferred event
+    default fn _emit_child_transferred_event(
+        &self,
+        _from: &Id,
+        _to: &Id,
+        _child_collection_address: &AccountId,
+        _child_token_id: &Id,
+        _pending: bool,
+    ) {
+    }
 }
@@ -4,6 +4,7 @@ use rmrk_common::{
     types::*,
 };
 
+use ink_prelude::vec::Vec;
 use openbrush::{
     contracts::psp34::Id,
     traits::AccountId,
@@ -15,7 +16,7 @@ pub type NestingRef = dyn Nesting;
 /// Trait definitions for Nesting ink! messages
 #[openbrush::trait_definition]
 pub trait Nesting {
-    /// Add a child NFT (from different collection) to the NFT in this collection.
+    /// Add a child NFT (from any collection, including this one) to the NFT in this collection.
     /// The status of the added child is `Pending` if caller is not owner of child NFT
     /// The status of the added child is `Accepted` if caller is is owner of child NFT
     /// The caller needs not to be the owner of the to_parent_token_id, but
@@ -27,6 +28,8 @@ pub trait Nesting {
     /// * `to_parent_token_id` must exist.
     /// * `child_token_id` must exist.
     /// * There cannot be two identical children.
+    /// * `to_parent_token_id` must not be nested (directly or indirectly) under the child.
+    /// * The child must not be nested in, or owned by, this contract.
     ///
     /// # Arguments:
     /// * `to_parent_token_id`: is the tokenId of the parent NFT. The receiver of child.
@@ -34,8 +37,8 @@ pub trait Nesting {
     ///
     /// # Result:
     /// Ownership of child NFT will be transferred to this contract (cross contract call)
-    /// On success emitts `RmrkEvent::ChildAdded`
-    /// On success emitts `RmrkEvent::ChildAccepted` - only if caller is already owner of child NFT
+    /// On success emitts `RmrkEvent::ChildAdded` - if the child is pending
+    /// On success emitts `RmrkEvent::ChildAccepted` - instead, if the caller owns the parent
     #[ink(message)]
     fn add_child(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<()>;
 
@@ -56,6 +59,29 @@ pub trait Nesting {
     #[ink(message)]
     fn remove_child(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<()>;
 
+    /// Remove a child NFT (from different collection) from token_id in this collection
+    /// and transfer it to any account.
+    ///
+    /// # Requirements:
+    /// * Caller must be the owner of `parent_token_id`
+    /// * The status of the child is `Accepted`
+    ///
+    /// # Arguments:
+    /// * `parent_token_id`: is the tokenId of the parent NFT.
+    /// * `child_nft`: (collection_id, token_id) of the child instance.
+    /// * `to`: account receiving the child NFT
+    ///
+    /// # Result:
+    /// Ownership of child NFT will be transferred to `to` (cross contract call)
+    /// On success emitts `RmrkEvent::ChildRemoved`
+    #[ink(message)]
+    fn remove_child_to(
+        &mut self,
+        parent_token_id: Id,
+        child_nft: ChildNft,
+        to: AccountId,
+    ) -> Result<()>;
+
     /// Accept a child NFT (from different collection) to be owned by parent token.
     ///
     /// # Requirements:
@@ -81,15 +107,32 @@ pub trait Nesting {
     /// * `child_nft`: (collection_id, token_id) of the child instance.
     ///
     /// # Result:
-    /// Child Nft is removed from pending
+    /// Child Nft is removed from pending and returned to the account which sent it.
+    /// The rejection succeeds even if the child contract refuses the return transfer. The child
+    /// then stays owned by this contract and can be recovered with `reclaim_child`.
     /// On success emitts `RmrkEvent::ChildRejected`
     #[ink(message)]
     fn reject_child(&mut self, parent_token_id: Id, child_nft: ChildNft) -> Result<()>;
 
+    /// Reject all pending children of the parent token.
+    /// Used by the parent owner to clear spam in one call.
+    ///
+    /// # Arguments:
+    /// * `parent_token_id`: is the tokenId of the parent NFT.
+    ///
+    /// # Result:
+    /// All pending children are removed and returned to the accounts which sent them.
+    /// A child whose return transfer fails stays owned by this contract, see `reclaim_child`.
+    /// Returns the number of rejected children.
+    /// On success emitts `RmrkEvent::ChildRejected` for each child
+    #[ink(message)]
+    fn reject_all_pending(&mut self, parent_token_id: Id) -> Result<u64>;
+
     /// Transfer the child NFT from one parent to another (in this collection).
     ///
     /// # Requirements:
     /// * The status of the child is `Accepted`
+    /// * `new_parent` must not be nested (directly or indirectly) under the child.
     ///
     /// # Arguments:
     /// * `current_parent`: current parent tokenId which holds child nft
@@ -97,12 +140,45 @@ pub trait Nesting {
     /// * `child_nft`: (collection_id, token_id) of the child instance.
     ///
     /// # Result:
-    /// Ownership of child NFT will be transferred to this contract (cross contract call)
-    /// On success emitts `RmrkEvent::ChildAdded`
-    /// On success emitts `RmrkEvent::ChildAccepted` - only if caller is already owner of child NFT
+    /// Child NFT stays owned by this contract
+    /// On success emitts `RmrkEvent::ChildTransferred`
+    /// The child is pending on the new parent unless both parents have the same owner
     #[ink(message)]
     fn transfer_child(&mut self, from: Id, to: Id, child_nft: ChildNft) -> Result<()>;
 
+    /// Send the child NFT from the parent token to a parent token of another RMRK contract.
+    /// The child is removed from this parent and nested into `dest_parent_id` in one call,
+    /// using `add_child` on the destination contract.
+    ///
+    /// # Requirements:
+    /// * Caller must be the owner of `parent_token_id`
+    /// * The status of the child is `Accepted`
+    /// * `dest_contract` must accept the child collection and `dest_parent_id` must exist.
+    ///
+    /// # Arguments:
+    /// * `parent_token_id`: current parent tokenId which holds child nft
+    /// * `child_nft`: (collection_id, token_id) of the child instance.
+    /// * `dest_contract`: address of the RMRK contract holding the new parent
+    /// * `dest_parent_id`: tokenId of the new parent in `dest_contract`
+    ///
+    /// # Result:
+    /// Ownership of child NFT will be transferred to `dest_contract` (cross contract call)
+    /// On success emitts `RmrkEvent::ChildRemoved`
+    /// Destination contract emitts `RmrkEvent::ChildAdded`
+    ///
+    /// Unless the caller owns `dest_parent_id`, the child is pending on the destination and
+    /// this contract is recorded as its sender. If the destination owner rejects it, the child
+    /// returns to this contract, which no longer tracks it under `parent_token_id`. It stays
+    /// there until the admin recovers it with `reclaim_child`.
+    #[ink(message)]
+    fn send_child_to(
+        &mut self,
+        parent_token_id: Id,
+        child_nft: ChildNft,
+        dest_contract: AccountId,
+        dest_parent_id: Id,
+    ) -> Result<()>;
+
     /// Read the number of children on the parent token.
     /// # Arguments:
     /// * `parent_token_id`: parent tokenId to check
@@ -111,9 +187,116 @@ pub trait Nesting {
     /// Returns the tupple of `(accepted_children, pending_children)` count
     #[ink(message)]
     fn children_balance(&self, parent_token_id: Id) -> Result<(u64, u64)>;
+
+    /// Read the parent token holding the child NFT.
+    /// Used by other RMRK contracts to walk the ancestor chain of a token.
+    /// # Arguments:
+    /// * `child_nft`: (collection_id, token_id) of the child instance.
+    ///
+    /// # Result:
+    /// Returns the parent tokenId if the child is pending or accepted on any token
+    #[ink(message)]
+    fn get_parent_of_child(&self, child_nft: ChildNft) -> Option<Id>;
+
+    /// Check if the child NFT is pending or accepted on any token of this contract.
+    /// Used by child contracts to refuse PSP34 transfers of their nested tokens.
+    /// # Arguments:
+    /// * `child_nft`: (collection_id, token_id) of the child instance.
+    #[ink(message)]
+    fn is_child_of(&self, child_nft: ChildNft) -> bool;
+
+    /// Set the maximum number of pending and accepted children per parent token.
+    /// Adding a child beyond the limit fails with `MaxPendingChildrenReached` or
+    /// `MaxAcceptedChildrenReached`. Zero restores the default limit.
+    ///
+    /// # Requirements:
+    /// * Caller must have `CONTRIBUTOR` role
+    ///
+    /// # Arguments:
+    /// * `max_pending`: maximum number of pending children per parent token
+    /// * `max_accepted`: maximum number of accepted children per parent token
+    #[ink(message)]
+    fn set_children_limits(&mut self, max_pending: u32, max_accepted: u32) -> Result<()>;
+
+    /// Read the maximum number of children per parent token.
+    ///
+    /// # Result:
+    /// Returns the tupple of `(max_pending_children, max_accepted_children)`
+    #[ink(message)]
+    fn get_children_limits(&self) -> (u32, u32);
+
+    /// Transfer a child NFT owned by this contract which is not nested in any parent token.
+    /// Recovers children stranded before their senders were recorded, or sent to this
+    /// contract with a plain PSP34 transfer.
+    ///
+    /// # Requirements:
+    /// * Caller must have `ADMIN` role
+    /// * The child is neither pending nor accepted on any parent token, including parents which
+    ///   are not migrated yet. Looking through parents which aren't migrated goes through every
+    ///   token, so migrate children first on large collections.
+    ///
+    /// # Arguments:
+    /// * `child_nft`: (collection_id, token_id) of the child instance.
+    /// * `to`: account receiving the child NFT
+    ///
+    /// # Result:
+    /// Ownership of child NFT will be transferred to `to` (cross contract call)
+    #[ink(message)]
+    fn reclaim_child(&mut self, child_nft: ChildNft, to: AccountId) -> Result<()>;
+
+    /// Move children of the given parent tokens from the previous storage layout,
+    /// where each parent token held a single `Vec<ChildNft>`.
+    /// Needs to be called for every parent token with children after upgrading a deployed contract.
+    ///
+    /// # Requirements:
+    /// * Caller must have `ADMIN` role
+    ///
+    /// # Arguments:
+    /// * `parent_token_ids`: parent tokens whose children are migrated
+    #[ink(message)]
+    fn migrate_children(&mut self, parent_token_ids: Vec<Id>) -> Result<()>;
+
+    /// Read the parent tokens which still hold children in the previous storage layout.
+    /// Scans the tokens of this collection in enumeration order, so the migration is finished
+    /// once every page up to `total_supply` comes back empty.
+    ///
+    /// # Arguments:
+    /// * `offset`: index of the first token to scan
+    /// * `limit`: maximum number of tokens to scan
+    ///
+    /// # Result:
+    /// Returns the scanned parent tokenIds to pass to `migrate_children`
+    #[ink(message)]
+    fn get_unmigrated_parents(&self, offset: u32, limit: u32) -> Vec<Id>;
 }
 
-/// Trait definitions for Nesting ink events
+/// Trait definitions for Nesting hooks
+#[openbrush::trait_definition]
+pub trait NestingHooks {
+    /// Called before an accepted child leaves the parent token.
+    /// Fails to keep the child on the parent token.
+    fn _before_child_removed(&mut self, parent_token_id: &Id, child_nft: &ChildNft) -> Result<()>;
+
+    /// Ensure that a token of this collection, nested into a token of another contract,
+    /// is only moved by that parent contract.
+    /// Meant to be called from the PSP34 `_before_token_transfer` hook of the child contract.
+    /// Fails if the owning contract can't be asked, e.g. when it doesn't implement Nesting.
+    fn _ensure_transfer_allowed_by_parent(
+        &self,
+        from: Option<&AccountId>,
+        token_id: &Id,
+    ) -> Result<()>;
+}
+
+/// Trait definitions for Nesting ink events.
+/// Every message emits exactly one event per state transition of a child:
+/// * `ChildAdded` - child is added to a parent token as pending
+/// * `ChildAccepted` - child becomes accepted, by `accept_child` or by `add_child` when the caller
+///   owns the parent token
+/// * `ChildRejected` - pending child is rejected
+/// * `ChildRemoved` - accepted child leaves this contract
+/// * `ChildTransferred` - accepted child moves to another parent token in this contract, as
+///   pending or accepted
 #[openbrush::trait_definition]
 pub trait NestingEvents {
     /// Emit ChildAdded event.
@@ -127,12 +310,13 @@ pub trait NestingEvents {
         child_token_id: &Id,
     );
 
-    /// Emit ChildAccepted event.
+    /// Emit ChildRemoved event.
     fn _emit_child_removed_event(
         &self,
         parent: &Id,
         child_collection_address: &AccountId,
         child_token_id: &Id,
+        to: &AccountId,
     );
 
     /// Emit ChildRejected event.
@@ -142,4 +326,15 @@ pub trait NestingEvents {
         child_collection_address: &AccountId,
         child_token_id: &Id,
     );
+
+    /// Emit ChildTransferred event.
+    /// `pending` is `true` if the child is pending on the new parent token.
+    fn _emit_child_transferred_event(
+        &self,
+        from: &Id,
+        to: &Id,
+        child_collection_address: &AccountId,
+        child_token_id: &Id,
+        pending: bool,
+    );
 }
@@ -0,0 +1,146 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink_env::DefaultEnvironment;
+use ink_lang as ink;
+use ink_primitives::{
+    Key,
+    KeyPtr,
+};
+use ink_storage::traits::SpreadAllocate;
+use openbrush::{
+    contracts::psp34::Id,
+    traits::AccountId,
+};
+use rmrk_common::{
+    errors::RmrkError,
+    types::ChildNft,
+};
+use rmrk_nesting::ChildrenList;
+
+const PARENT_ID: Id = Id::U64(1);
+const OTHER_PARENT_ID: Id = Id::U64(2);
+
+fn children_list() -> ChildrenList {
+    ChildrenList::allocate_spread(&mut KeyPtr::from(Key::from([0x42; 32])))
+}
+
+fn child(token_id: u64) -> ChildNft {
+    (AccountId::from([0x1; 32]), Id::U64(token_id))
+}
+
+#[ink::test]
+fn push_and_contains_works() {
+    let mut list = children_list();
+    assert_eq!(list.len(&PARENT_ID), 0);
+    assert!(!list.contains(&PARENT_ID, &child(1)));
+
+    list.push(&PARENT_ID, &child(1));
+    list.push(&PARENT_ID, &child(2));
+    assert_eq!(list.len(&PARENT_ID), 2);
+    assert!(list.contains(&PARENT_ID, &child(1)));
+    assert!(list.contains(&PARENT_ID, &child(2)));
+    assert_eq!(list.list(&PARENT_ID), vec![child(1), child(2)]);
+
+    // lists of other parents are not affected
+    assert_eq!(list.len(&OTHER_PARENT_ID), 0);
+    assert!(!list.contains(&OTHER_PARENT_ID, &child(1)));
+}
+
+#[ink::test]
+fn remove_moves_last_child() {
+    let mut list = children_list();
+    for token_id in 1..=4 {
+        list.push(&PARENT_ID, &child(token_id));
+    }
+
+    assert!(list.remove(&PARENT_ID, &child(2)).is_ok());
+    assert_eq!(list.len(&PARENT_ID), 3);
+    assert!(!list.contains(&PARENT_ID, &child(2)));
+    assert_eq!(list.list(&PARENT_ID), vec![child(1), child(4), child(3)]);
+
+    // removing the last child doesn't move any other child
+    assert!(list.remove(&PARENT_ID, &child(3)).is_ok());
+    assert_eq!(list.list(&PARENT_ID), vec![child(1), child(4)]);
+
+    assert_eq!(
+        list.remove(&PARENT_ID, &child(2)),
+        Err(RmrkError::ChildNotFound.into())
+    );
+    assert_eq!(
+        list.remove(&OTHER_PARENT_ID, &child(1)),
+        Err(RmrkError::ChildNotFound.into())
+    );
+}
+
+#[ink::test]
+fn clear_works() {
+    let mut list = children_list();
+    for token_id in 1..=3 {
+        list.push(&PARENT_ID, &child(token_id));
+    }
+    list.push(&OTHER_PARENT_ID, &child(4));
+
+    assert_eq!(list.clear(&PARENT_ID), vec![child(1), child(2), child(3)]);
+    assert_eq!(list.len(&PARENT_ID), 0);
+    assert!(!list.contains(&PARENT_ID, &child(1)));
+    assert_eq!(list.get(&PARENT_ID, 0), None);
+    assert_eq!(list.list(&OTHER_PARENT_ID), vec![child(4)]);
+}
+
+#[ink::test]
+fn large_list_works() {
+    const CHILDREN: u64 = 500;
+    let mut list = children_list();
+    for token_id in 0..CHILDREN {
+        list.push(&PARENT_ID, &child(token_id));
+    }
+    assert_eq!(list.len(&PARENT_ID), CHILDREN as u32);
+
+    // every lookup and removal touches a fixed number of entries
+    for token_id in (0..CHILDREN).step_by(2) {
+        assert!(list.remove(&PARENT_ID, &child(token_id)).is_ok());
+    }
+    assert_eq!(list.len(&PARENT_ID), (CHILDREN / 2) as u32);
+    for token_id in 0..CHILDREN {
+        assert_eq!(
+            list.contains(&PARENT_ID, &child(token_id)),
+            token_id % 2 == 1
+        );
+    }
+}
+
+/// Storage reads and writes done by `operation`.
+fn storage_cost(operation: impl FnOnce()) -> (usize, usize) {
+    let contract = ink_env::test::callee::<DefaultEnvironment>();
+    let (reads, writes) = ink_env::test::get_contract_storage_rw::<DefaultEnvironment>(&contract);
+    operation();
+    let (reads_after, writes_after) =
+        ink_env::test::get_contract_storage_rw::<DefaultEnvironment>(&contract);
+    (reads_after - reads, writes_after - writes)
+}
+
+#[ink::test]
+fn benchmark_cost_per_children_count() {
+    const CHILDREN_COUNTS: [u64; 4] = [1, 10, 100, 1000];
+    let mut list = children_list();
+
+    let mut costs = Vec::new();
+    for (parent, count) in CHILDREN_COUNTS.iter().enumerate() {
+        let parent_token_id = Id::U64(parent as u64);
+        for token_id in 0..*count {
+            list.push(&parent_token_id, &child(token_id));
+        }
+
+        let push = storage_cost(|| list.push(&parent_token_id, &child(*count)));
+        let contains = storage_cost(|| {
+            assert!(list.contains(&parent_token_id, &child(0)));
+        });
+        let remove = storage_cost(|| {
+            assert!(list.remove(&parent_token_id, &child(0)).is_ok());
+        });
+        costs.push((push, contains, remove));
+    }
+
+    // each operation touches the same storage entries regardless of the number of children
+    assert!(costs.iter().all(|cost| *cost == costs[0]));
+}
@@ -23,7 +23,7 @@ pub mod utils {
 }
 
 pub mod storage {
-    #[cfg(feature = "equippable")]
+    #[cfg(feature = "base")]
     pub use rmrk_base::*;
     #[cfg(feature = "equippable")]
     pub use rmrk_equippable::*;
@@ -36,7 +36,7 @@ pub mod storage {
 }
 
 pub mod traits {
-    #[cfg(feature = "equippable")]
+    #[cfg(feature = "base")]
     pub use rmrk_base::traits::*;
     #[cfg(feature = "equippable")]
     pub use rmrk_equippable::traits::*;
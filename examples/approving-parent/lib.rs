@@ -0,0 +1,66 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+use openbrush::{
+    contracts::psp34::Id,
+    traits::AccountId,
+};
+
+/// Nesting query answered by the test parent.
+/// Trait and message names match the RMRK `Nesting` trait, so child contracts reach it with
+/// `NestingRef::is_child_of`.
+#[openbrush::trait_definition]
+pub trait Nesting {
+    #[ink(message)]
+    fn is_child_of(&self, child_nft: (AccountId, Id)) -> bool;
+}
+
+/// Parent contract used in tests, which approves operators for the child tokens it holds.
+#[openbrush::contract]
+pub mod rmrk_example_approving_parent {
+    use ink_storage::{
+        traits::SpreadAllocate,
+        Mapping,
+    };
+    use openbrush::contracts::psp34::*;
+
+    use crate::Nesting;
+
+    // Approving parent contract storage
+    #[ink(storage)]
+    #[derive(Default, SpreadAllocate)]
+    pub struct ApprovingParent {
+        nested: Mapping<(AccountId, Id), bool>,
+    }
+
+    impl Nesting for ApprovingParent {
+        #[ink(message)]
+        fn is_child_of(&self, child_nft: (AccountId, Id)) -> bool {
+            self.nested.get(&child_nft).unwrap_or(false)
+        }
+    }
+
+    impl ApprovingParent {
+        /// Instantiate new approving parent contract
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            ink_lang::codegen::initialize_contract(|_instance: &mut ApprovingParent| {})
+        }
+
+        /// Set whether a child token held by this contract is reported as nested
+        #[ink(message)]
+        pub fn set_nested(&mut self, child_nft: (AccountId, Id), nested: bool) {
+            self.nested.insert(&child_nft, &nested);
+        }
+
+        /// Approve `operator` to transfer a child token held by this contract
+        #[ink(message)]
+        pub fn approve_child(
+            &mut self,
+            child_nft: (AccountId, Id),
+            operator: AccountId,
+        ) -> Result<(), PSP34Error> {
+            PSP34Ref::approve(&child_nft.0, operator, Some(child_nft.1), true)
+        }
+    }
+}
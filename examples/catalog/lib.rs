@@ -0,0 +1,182 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// Standalone Base contract.
+/// Collections reference it by address in their asset entries, so they can share one catalog of
+/// parts and slots.
+#[openbrush::contract]
+pub mod rmrk_example_catalog {
+    use ink_lang::codegen::{
+        EmitEvent,
+        Env,
+    };
+
+    use ink_storage::traits::SpreadAllocate;
+    use openbrush::{
+        contracts::access_control::*,
+        traits::{
+            Storage,
+            String,
+        },
+    };
+
+    use rmrk::{
+        roles::CONTRIBUTOR,
+        storage::*,
+        traits::*,
+        types::*,
+    };
+
+    /// Event emitted when the details of a part are updated.
+    #[ink(event)]
+    pub struct PartUpdated {
+        #[ink(topic)]
+        part: PartId,
+    }
+
+    /// Event emitted when a part is removed from the base.
+    #[ink(event)]
+    pub struct PartRemoved {
+        #[ink(topic)]
+        part: PartId,
+    }
+
+    /// Event emitted when a collection can no longer be equipped into a slot.
+    #[ink(event)]
+    pub struct EquippableAddressRemoved {
+        #[ink(topic)]
+        part: PartId,
+        #[ink(topic)]
+        equippable_address: AccountId,
+    }
+
+    // Catalog contract storage
+    #[ink(storage)]
+    #[derive(Default, SpreadAllocate, Storage)]
+    pub struct Catalog {
+        #[storage_field]
+        access: access_control::Data,
+        #[storage_field]
+        base: BaseData,
+    }
+
+    impl AccessControl for Catalog {}
+
+    impl Base for Catalog {}
+
+    impl BaseEvents for Catalog {
+        /// Used to notify listeners that the details of a part are updated.
+        fn _emit_part_updated_event(&self, part_id: PartId) {
+            self.env().emit_event(PartUpdated { part: part_id });
+        }
+
+        /// Used to notify listeners that a part is removed from the base.
+        fn _emit_part_removed_event(&self, part_id: PartId) {
+            self.env().emit_event(PartRemoved { part: part_id });
+        }
+
+        /// Used to notify listeners that a collection can no longer be equipped into a slot.
+        fn _emit_equippable_address_removed_event(
+            &self,
+            part_id: PartId,
+            equippable_address: AccountId,
+        ) {
+            self.env().emit_event(EquippableAddressRemoved {
+                part: part_id,
+                equippable_address,
+            });
+        }
+    }
+
+    impl Catalog {
+        /// Instantiate new Catalog contract
+        #[ink(constructor)]
+        pub fn new(base_metadata: String) -> Self {
+            let caller = Self::env().caller();
+            ink_lang::codegen::initialize_contract(|instance: &mut Catalog| {
+                instance._init_with_admin(caller);
+                instance._setup_role(CONTRIBUTOR, caller);
+                instance.base.base_metadata_uri = base_metadata;
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{
+            Catalog,
+            Environment,
+        };
+
+        use ink_env::test;
+        use ink_lang as ink;
+
+        use openbrush::{
+            contracts::access_control::{
+                AccessControl,
+                AccessControlError::*,
+            },
+            traits::String,
+        };
+
+        use rmrk::{
+            errors::*,
+            roles::CONTRIBUTOR,
+            traits::Base,
+            types::*,
+        };
+
+        const BASE_METADATA: &str = "ipfs://baseMetadata";
+
+        fn init() -> Catalog {
+            Catalog::new(String::from(BASE_METADATA))
+        }
+
+        fn default_accounts() -> test::DefaultAccounts<ink_env::DefaultEnvironment> {
+            test::default_accounts::<Environment>()
+        }
+
+        #[ink::test]
+        fn init_works() {
+            let accounts = default_accounts();
+            let catalog = init();
+            assert_eq!(catalog.get_base_metadata(), BASE_METADATA);
+            assert!(catalog.has_role(CONTRIBUTOR, accounts.alice));
+            assert_eq!(catalog.get_parts_count(), 0);
+        }
+
+        #[ink::test]
+        fn add_parts_works() {
+            let accounts = default_accounts();
+            let parts = vec![
+                Part {
+                    part_type: PartType::Fixed,
+                    z: 0,
+                    equippable: vec![],
+                    metadata_uri: String::from("ipfs://backgrounds/1.svg"),
+                    is_equippable_by_all: false,
+                },
+                Part {
+                    part_type: PartType::Slot,
+                    z: 1,
+                    equippable: vec![accounts.bob],
+                    metadata_uri: String::from("ipfs://slots/1.svg"),
+                    is_equippable_by_all: false,
+                },
+            ];
+
+            let mut catalog = init();
+            assert!(catalog.add_part_list(parts).is_ok());
+            assert_eq!(catalog.get_part_ids(0, 10), vec![0, 1]);
+            assert!(catalog.ensure_parts_exist(&None, &vec![0, 1]).is_ok());
+            assert_eq!(
+                catalog.ensure_parts_exist(&None, &vec![2]),
+                Err(RmrkError::UnknownPartId.into())
+            );
+            assert!(catalog.check_slot_equippable(1, accounts.bob).is_ok());
+
+            test::set_caller::<Environment>(accounts.bob);
+            assert_eq!(catalog.add_part_list(vec![]), Err(MissingRole.into()));
+        }
+    }
+}
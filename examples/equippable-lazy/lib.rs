@@ -25,6 +25,7 @@ pub mod rmrk_example_equippable {
     };
 
     use rmrk::{
+        errors::Result,
         storage::*,
         traits::*,
         types::*,
@@ -85,6 +86,7 @@ pub mod rmrk_example_equippable {
         child_collection: AccountId,
         #[ink(topic)]
         child_token_id: Id,
+        to: AccountId,
     }
 
     /// Event emitted when a child is rejected.
@@ -98,12 +100,56 @@ pub mod rmrk_example_equippable {
         child_token_id: Id,
     }
 
+    /// Event emitted when a child is transferred to another parent.
+    #[ink(event)]
+    pub struct ChildTransferred {
+        #[ink(topic)]
+        from: Id,
+        #[ink(topic)]
+        to: Id,
+        #[ink(topic)]
+        child_collection: AccountId,
+        child_token_id: Id,
+        pending: bool,
+    }
+
     /// Event emitted when new asset is set for the collection.
     #[ink(event)]
     pub struct AssetSet {
         #[ink(topic)]
         asset: AssetId,
     }
+
+    /// Event emitted when the uri of an asset entry is updated.
+    #[ink(event)]
+    pub struct AssetUriSet {
+        #[ink(topic)]
+        asset: AssetId,
+    }
+
+    /// Event emitted when the uri of an asset is set for a single token.
+    #[ink(event)]
+    pub struct TokenAssetUriSet {
+        #[ink(topic)]
+        token: Id,
+        #[ink(topic)]
+        asset: AssetId,
+    }
+
+    /// Event emitted when an asset entry is deprecated.
+    #[ink(event)]
+    pub struct AssetDeprecated {
+        #[ink(topic)]
+        asset: AssetId,
+    }
+
+    /// Event emitted when an asset entry is removed from the collection.
+    #[ink(event)]
+    pub struct AssetEntryRemoved {
+        #[ink(topic)]
+        asset: AssetId,
+    }
+
     /// Event emitted when the asset is added to the token.
     #[ink(event)]
     pub struct AssetAddedToToken {
@@ -122,6 +168,8 @@ pub mod rmrk_example_equippable {
         token: Id,
         #[ink(topic)]
         asset: AssetId,
+        #[ink(topic)]
+        replaces: Option<AssetId>,
     }
 
     /// Event emitted when the asset is rejected.
@@ -142,13 +190,34 @@ pub mod rmrk_example_equippable {
         asset: AssetId,
     }
 
-    /// Event emitted when the asset is removed.
+    /// Event emitted when the asset priorities are set.
     #[ink(event)]
     pub struct AssetPrioritySet {
         #[ink(topic)]
         token: Id,
         #[ink(topic)]
-        priorities: Vec<AssetId>,
+        priorities: Vec<u16>,
+    }
+
+    /// Event emitted when an account is approved to manage the token's assets.
+    #[ink(event)]
+    pub struct ApprovalForAssets {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: Option<AccountId>,
+        #[ink(topic)]
+        token: Id,
+    }
+
+    /// Event emitted when an operator is approved or removed for all owner's assets.
+    #[ink(event)]
+    pub struct ApprovalForAllForAssets {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
     }
 
     /// Event emitted when the asset is equipped.
@@ -187,6 +256,29 @@ pub mod rmrk_example_equippable {
         parent: AccountId,
     }
 
+    /// Event emitted when the details of a part are updated.
+    #[ink(event)]
+    pub struct PartUpdated {
+        #[ink(topic)]
+        part: PartId,
+    }
+
+    /// Event emitted when a part is removed from the base.
+    #[ink(event)]
+    pub struct PartRemoved {
+        #[ink(topic)]
+        part: PartId,
+    }
+
+    /// Event emitted when a collection can no longer be equipped into a slot.
+    #[ink(event)]
+    pub struct EquippableAddressRemoved {
+        #[ink(topic)]
+        part: PartId,
+        #[ink(topic)]
+        equippable_address: AccountId,
+    }
+
     // Rmrk contract storage
     #[ink(storage)]
     #[derive(Default, SpreadAllocate, Storage)]
@@ -258,6 +350,22 @@ pub mod rmrk_example_equippable {
     }
 
     impl psp34::Internal for Rmrk {
+        /// Nested tokens can only be moved by their parent contract.
+        /// Asset approvals of the previous owner are cleared and assets of a burned token released.
+        fn _before_token_transfer(
+            &mut self,
+            from: Option<&AccountId>,
+            to: Option<&AccountId>,
+            id: &Id,
+        ) -> core::result::Result<(), PSP34Error> {
+            self._ensure_transfer_allowed_by_parent(from, id)?;
+            self.clear_asset_approval(from, id);
+            if to.is_none() {
+                self.release_token_assets(id);
+            }
+            Ok(())
+        }
+
         /// Emit Transfer event
         fn _emit_transfer_event(&self, from: Option<AccountId>, to: Option<AccountId>, id: Id) {
             self.env().emit_event(Transfer { from, to, id });
@@ -280,6 +388,43 @@ pub mod rmrk_example_equippable {
         }
     }
 
+    impl NestingHooks for Rmrk {
+        /// Children equipped into a slot of the parent token can't leave it
+        fn _before_child_removed(
+            &mut self,
+            parent_token_id: &Id,
+            child_nft: &ChildNft,
+        ) -> Result<()> {
+            self.ensure_child_not_equipped(parent_token_id, child_nft)
+        }
+    }
+
+    impl MultiAssetHooks for Rmrk {
+        /// Asset parts must exist in the local base, or in the base referenced by address
+        fn _validate_asset_parts(
+            &self,
+            base_address: &Option<AccountId>,
+            part_ids: &Vec<PartId>,
+        ) -> Result<()> {
+            self.ensure_parts_exist(base_address, part_ids)
+        }
+    }
+
+    impl BaseHooks for Rmrk {
+        /// Parts referenced by an asset entry of this collection or with a child equipped can't be
+        /// removed
+        fn _ensure_part_removable(&self, part_id: PartId) -> Result<()> {
+            self.ensure_part_unreferenced(part_id)?;
+            self.ensure_slot_unequipped(part_id)
+        }
+
+        /// Parts referenced by an asset entry or with a child equipped can't change type
+        fn _ensure_part_type_changeable(&self, part_id: PartId) -> Result<()> {
+            self.ensure_part_unreferenced(part_id)?;
+            self.ensure_slot_unequipped(part_id)
+        }
+    }
+
     impl NestingEvents for Rmrk {
         /// Emit ChildAdded event
         fn _emit_added_child_event(&self, to: &Id, collection: &AccountId, child: &Id) {
@@ -305,11 +450,13 @@ pub mod rmrk_example_equippable {
             parent: &Id,
             child_collection: &AccountId,
             child_token_id: &Id,
+            to: &AccountId,
         ) {
             self.env().emit_event(ChildRemoved {
                 parent: parent.clone(),
                 child_collection: *child_collection,
                 child_token_id: child_token_id.clone(),
+                to: *to,
             });
         }
 
@@ -326,6 +473,24 @@ pub mod rmrk_example_equippable {
                 child_token_id: child_token_id.clone(),
             });
         }
+
+        /// Emit ChildTransferred event
+        fn _emit_child_transferred_event(
+            &self,
+            from: &Id,
+            to: &Id,
+            child_collection: &AccountId,
+            child_token_id: &Id,
+            pending: bool,
+        ) {
+            self.env().emit_event(ChildTransferred {
+                from: from.clone(),
+                to: to.clone(),
+                child_collection: *child_collection,
+                child_token_id: child_token_id.clone(),
+                pending,
+            });
+        }
     }
 
     impl MultiAssetEvents for Rmrk {
@@ -349,10 +514,16 @@ pub mod rmrk_example_equippable {
         }
 
         /// Used to notify listeners that an asset object at `assetId` is accepted by the token and migrated
-        fn _emit_asset_accepted_event(&self, token_id: &Id, asset_id: &AssetId) {
+        fn _emit_asset_accepted_event(
+            &self,
+            token_id: &Id,
+            asset_id: &AssetId,
+            replaces_id: &Option<AssetId>,
+        ) {
             self.env().emit_event(AssetAccepted {
                 token: token_id.clone(),
                 asset: *asset_id,
+                replaces: *replaces_id,
             });
         }
 
@@ -373,12 +544,64 @@ pub mod rmrk_example_equippable {
         }
 
         /// Used to notify listeners that token's prioritiy array is reordered.
-        fn _emit_asset_priority_set_event(&self, token_id: &Id, priorities: Vec<AssetId>) {
+        fn _emit_asset_priority_set_event(&self, token_id: &Id, priorities: Vec<u16>) {
             self.env().emit_event(AssetPrioritySet {
                 token: token_id.clone(),
                 priorities,
             });
         }
+
+        /// Used to notify listeners that the uri of an asset entry is updated.
+        fn _emit_asset_uri_set_event(&self, asset_id: &AssetId) {
+            self.env().emit_event(AssetUriSet { asset: *asset_id });
+        }
+
+        /// Used to notify listeners that the uri of an asset is set for a single token.
+        fn _emit_token_asset_uri_set_event(&self, token_id: &Id, asset_id: &AssetId) {
+            self.env().emit_event(TokenAssetUriSet {
+                token: token_id.clone(),
+                asset: *asset_id,
+            });
+        }
+
+        /// Used to notify listeners that an asset entry can no longer be added to tokens.
+        fn _emit_asset_deprecated_event(&self, asset_id: &AssetId) {
+            self.env().emit_event(AssetDeprecated { asset: *asset_id });
+        }
+
+        /// Used to notify listeners that an asset entry is removed from the collection.
+        fn _emit_asset_entry_removed_event(&self, asset_id: &AssetId) {
+            self.env()
+                .emit_event(AssetEntryRemoved { asset: *asset_id });
+        }
+
+        /// Used to notify listeners that an account is approved to manage the token's assets.
+        fn _emit_approval_for_assets_event(
+            &self,
+            owner: &AccountId,
+            operator: &Option<AccountId>,
+            token_id: &Id,
+        ) {
+            self.env().emit_event(ApprovalForAssets {
+                owner: *owner,
+                operator: *operator,
+                token: token_id.clone(),
+            });
+        }
+
+        /// Used to notify listeners that an operator is approved or removed for all owner's assets.
+        fn _emit_approval_for_all_for_assets_event(
+            &self,
+            owner: &AccountId,
+            operator: &AccountId,
+            approved: bool,
+        ) {
+            self.env().emit_event(ApprovalForAllForAssets {
+                owner: *owner,
+                operator: *operator,
+                approved,
+            });
+        }
     }
 
     impl EquippableEvents for Rmrk {
@@ -428,4 +651,28 @@ pub mod rmrk_example_equippable {
             });
         }
     }
+
+    impl BaseEvents for Rmrk {
+        /// Used to notify listeners that the details of a part are updated.
+        fn _emit_part_updated_event(&self, part_id: PartId) {
+            self.env().emit_event(PartUpdated { part: part_id });
+        }
+
+        /// Used to notify listeners that a part is removed from the base.
+        fn _emit_part_removed_event(&self, part_id: PartId) {
+            self.env().emit_event(PartRemoved { part: part_id });
+        }
+
+        /// Used to notify listeners that a collection can no longer be equipped into a slot.
+        fn _emit_equippable_address_removed_event(
+            &self,
+            part_id: PartId,
+            equippable_address: AccountId,
+        ) {
+            self.env().emit_event(EquippableAddressRemoved {
+                part: part_id,
+                equippable_address,
+            });
+        }
+    }
 }
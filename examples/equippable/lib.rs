@@ -25,6 +25,7 @@ pub mod rmrk_example_equippable {
     };
 
     use rmrk::{
+        errors::Result,
         storage::*,
         traits::*,
         types::*,
@@ -85,6 +86,7 @@ pub mod rmrk_example_equippable {
         child_collection: AccountId,
         #[ink(topic)]
         child_token_id: Id,
+        to: AccountId,
     }
 
     /// Event emitted when a child is rejected.
@@ -98,12 +100,55 @@ pub mod rmrk_example_equippable {
         child_token_id: Id,
     }
 
+    /// Event emitted when a child is transferred to another parent.
+    #[ink(event)]
+    pub struct ChildTransferred {
+        #[ink(topic)]
+        from: Id,
+        #[ink(topic)]
+        to: Id,
+        #[ink(topic)]
+        child_collection: AccountId,
+        child_token_id: Id,
+        pending: bool,
+    }
+
     /// Event emitted when new asset is set for the collection.
     #[ink(event)]
     pub struct AssetSet {
         #[ink(topic)]
         asset: AssetId,
     }
+    /// Event emitted when the uri of an asset entry is updated.
+    #[ink(event)]
+    pub struct AssetUriSet {
+        #[ink(topic)]
+        asset: AssetId,
+    }
+
+    /// Event emitted when the uri of an asset is set for a single token.
+    #[ink(event)]
+    pub struct TokenAssetUriSet {
+        #[ink(topic)]
+        token: Id,
+        #[ink(topic)]
+        asset: AssetId,
+    }
+
+    /// Event emitted when an asset entry is deprecated.
+    #[ink(event)]
+    pub struct AssetDeprecated {
+        #[ink(topic)]
+        asset: AssetId,
+    }
+
+    /// Event emitted when an asset entry is removed from the collection.
+    #[ink(event)]
+    pub struct AssetEntryRemoved {
+        #[ink(topic)]
+        asset: AssetId,
+    }
+
     /// Event emitted when the asset is added to the token.
     #[ink(event)]
     pub struct AssetAddedToToken {
@@ -122,6 +167,8 @@ pub mod rmrk_example_equippable {
         token: Id,
         #[ink(topic)]
         asset: AssetId,
+        #[ink(topic)]
+        replaces: Option<AssetId>,
     }
 
     /// Event emitted when the asset is rejected.
@@ -142,13 +189,34 @@ pub mod rmrk_example_equippable {
         asset: AssetId,
     }
 
-    /// Event emitted when the asset is removed.
+    /// Event emitted when the asset priorities are set.
     #[ink(event)]
     pub struct AssetPrioritySet {
         #[ink(topic)]
         token: Id,
         #[ink(topic)]
-        priorities: Vec<AssetId>,
+        priorities: Vec<u16>,
+    }
+
+    /// Event emitted when an account is approved to manage the token's assets.
+    #[ink(event)]
+    pub struct ApprovalForAssets {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: Option<AccountId>,
+        #[ink(topic)]
+        token: Id,
+    }
+
+    /// Event emitted when an operator is approved or removed for all owner's assets.
+    #[ink(event)]
+    pub struct ApprovalForAllForAssets {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
     }
 
     /// Event emitted when the asset is equipped.
@@ -187,6 +255,29 @@ pub mod rmrk_example_equippable {
         parent: AccountId,
     }
 
+    /// Event emitted when the details of a part are updated.
+    #[ink(event)]
+    pub struct PartUpdated {
+        #[ink(topic)]
+        part: PartId,
+    }
+
+    /// Event emitted when a part is removed from the base.
+    #[ink(event)]
+    pub struct PartRemoved {
+        #[ink(topic)]
+        part: PartId,
+    }
+
+    /// Event emitted when a collection can no longer be equipped into a slot.
+    #[ink(event)]
+    pub struct EquippableAddressRemoved {
+        #[ink(topic)]
+        part: PartId,
+        #[ink(topic)]
+        equippable_address: AccountId,
+    }
+
     // Rmrk contract storage
     #[ink(storage)]
     #[derive(Default, SpreadAllocate, Storage)]
@@ -225,6 +316,8 @@ pub mod rmrk_example_equippable {
 
     impl MultiAsset for Rmrk {}
 
+    impl MultiAssetTyped for Rmrk {}
+
     impl Base for Rmrk {}
 
     impl Equippable for Rmrk {}
@@ -258,6 +351,22 @@ pub mod rmrk_example_equippable {
     }
 
     impl psp34::Internal for Rmrk {
+        /// Nested tokens can only be moved by their parent contract.
+        /// Asset approvals of the previous owner are cleared and assets of a burned token released.
+        fn _before_token_transfer(
+            &mut self,
+            from: Option<&AccountId>,
+            to: Option<&AccountId>,
+            id: &Id,
+        ) -> core::result::Result<(), PSP34Error> {
+            self._ensure_transfer_allowed_by_parent(from, id)?;
+            self.clear_asset_approval(from, id);
+            if to.is_none() {
+                self.release_token_assets(id);
+            }
+            Ok(())
+        }
+
         /// Emit Transfer event
         fn _emit_transfer_event(&self, from: Option<AccountId>, to: Option<AccountId>, id: Id) {
             self.env().emit_event(Transfer { from, to, id });
@@ -280,6 +389,43 @@ pub mod rmrk_example_equippable {
         }
     }
 
+    impl NestingHooks for Rmrk {
+        /// Children equipped into a slot of the parent token can't leave it
+        fn _before_child_removed(
+            &mut self,
+            parent_token_id: &Id,
+            child_nft: &ChildNft,
+        ) -> Result<()> {
+            self.ensure_child_not_equipped(parent_token_id, child_nft)
+        }
+    }
+
+    impl MultiAssetHooks for Rmrk {
+        /// Asset parts must exist in the local base, or in the base referenced by address
+        fn _validate_asset_parts(
+            &self,
+            base_address: &Option<AccountId>,
+            part_ids: &Vec<PartId>,
+        ) -> Result<()> {
+            self.ensure_parts_exist(base_address, part_ids)
+        }
+    }
+
+    impl BaseHooks for Rmrk {
+        /// Parts referenced by an asset entry of this collection or with a child equipped can't be
+        /// removed
+        fn _ensure_part_removable(&self, part_id: PartId) -> Result<()> {
+            self.ensure_part_unreferenced(part_id)?;
+            self.ensure_slot_unequipped(part_id)
+        }
+
+        /// Parts referenced by an asset entry or with a child equipped can't change type
+        fn _ensure_part_type_changeable(&self, part_id: PartId) -> Result<()> {
+            self.ensure_part_unreferenced(part_id)?;
+            self.ensure_slot_unequipped(part_id)
+        }
+    }
+
     impl NestingEvents for Rmrk {
         /// Emit ChildAdded event
         fn _emit_added_child_event(&self, to: &Id, collection: &AccountId, child: &Id) {
@@ -305,11 +451,13 @@ pub mod rmrk_example_equippable {
             parent: &Id,
             child_collection: &AccountId,
             child_token_id: &Id,
+            to: &AccountId,
         ) {
             self.env().emit_event(ChildRemoved {
                 parent: parent.clone(),
                 child_collection: *child_collection,
                 child_token_id: child_token_id.clone(),
+                to: *to,
             });
         }
 
@@ -326,6 +474,24 @@ pub mod rmrk_example_equippable {
                 child_token_id: child_token_id.clone(),
             });
         }
+
+        /// Emit ChildTransferred event
+        fn _emit_child_transferred_event(
+            &self,
+            from: &Id,
+            to: &Id,
+            child_collection: &AccountId,
+            child_token_id: &Id,
+            pending: bool,
+        ) {
+            self.env().emit_event(ChildTransferred {
+                from: from.clone(),
+                to: to.clone(),
+                child_collection: *child_collection,
+                child_token_id: child_token_id.clone(),
+                pending,
+            });
+        }
     }
 
     impl MultiAssetEvents for Rmrk {
@@ -349,10 +515,16 @@ pub mod rmrk_example_equippable {
         }
 
         /// Used to notify listeners that an asset object at `assetId` is accepted by the token and migrated
-        fn _emit_asset_accepted_event(&self, token_id: &Id, asset_id: &AssetId) {
+        fn _emit_asset_accepted_event(
+            &self,
+            token_id: &Id,
+            asset_id: &AssetId,
+            replaces_id: &Option<AssetId>,
+        ) {
             self.env().emit_event(AssetAccepted {
                 token: token_id.clone(),
                 asset: *asset_id,
+                replaces: *replaces_id,
             });
         }
 
@@ -373,12 +545,64 @@ pub mod rmrk_example_equippable {
         }
 
         /// Used to notify listeners that token's prioritiy array is reordered.
-        fn _emit_asset_priority_set_event(&self, token_id: &Id, priorities: Vec<AssetId>) {
+        fn _emit_asset_priority_set_event(&self, token_id: &Id, priorities: Vec<u16>) {
             self.env().emit_event(AssetPrioritySet {
                 token: token_id.clone(),
                 priorities,
             });
         }
+
+        /// Used to notify listeners that the uri of an asset entry is updated.
+        fn _emit_asset_uri_set_event(&self, asset_id: &AssetId) {
+            self.env().emit_event(AssetUriSet { asset: *asset_id });
+        }
+
+        /// Used to notify listeners that the uri of an asset is set for a single token.
+        fn _emit_token_asset_uri_set_event(&self, token_id: &Id, asset_id: &AssetId) {
+            self.env().emit_event(TokenAssetUriSet {
+                token: token_id.clone(),
+                asset: *asset_id,
+            });
+        }
+
+        /// Used to notify listeners that an asset entry can no longer be added to tokens.
+        fn _emit_asset_deprecated_event(&self, asset_id: &AssetId) {
+            self.env().emit_event(AssetDeprecated { asset: *asset_id });
+        }
+
+        /// Used to notify listeners that an asset entry is removed from the collection.
+        fn _emit_asset_entry_removed_event(&self, asset_id: &AssetId) {
+            self.env()
+                .emit_event(AssetEntryRemoved { asset: *asset_id });
+        }
+
+        /// Used to notify listeners that an account is approved to manage the token's assets.
+        fn _emit_approval_for_assets_event(
+            &self,
+            owner: &AccountId,
+            operator: &Option<AccountId>,
+            token_id: &Id,
+        ) {
+            self.env().emit_event(ApprovalForAssets {
+                owner: *owner,
+                operator: *operator,
+                token: token_id.clone(),
+            });
+        }
+
+        /// Used to notify listeners that an operator is approved or removed for all owner's assets.
+        fn _emit_approval_for_all_for_assets_event(
+            &self,
+            owner: &AccountId,
+            operator: &AccountId,
+            approved: bool,
+        ) {
+            self.env().emit_event(ApprovalForAllForAssets {
+                owner: *owner,
+                operator: *operator,
+                approved,
+            });
+        }
     }
 
     impl EquippableEvents for Rmrk {
@@ -429,6 +653,30 @@ pub mod rmrk_example_equippable {
         }
     }
 
+    impl BaseEvents for Rmrk {
+        /// Used to notify listeners that the details of a part are updated.
+        fn _emit_part_updated_event(&self, part_id: PartId) {
+            self.env().emit_event(PartUpdated { part: part_id });
+        }
+
+        /// Used to notify listeners that a part is removed from the base.
+        fn _emit_part_removed_event(&self, part_id: PartId) {
+            self.env().emit_event(PartRemoved { part: part_id });
+        }
+
+        /// Used to notify listeners that a collection can no longer be equipped into a slot.
+        fn _emit_equippable_address_removed_event(
+            &self,
+            part_id: PartId,
+            equippable_address: AccountId,
+        ) {
+            self.env().emit_event(EquippableAddressRemoved {
+                part: part_id,
+                equippable_address,
+            });
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use super::{
@@ -471,6 +719,8 @@ pub mod rmrk_example_equippable {
                 Equippable,
                 Minting,
                 MultiAsset,
+                MultiAssetTyped,
+                Nesting,
             },
             types::*,
             utils::Utils,
@@ -553,6 +803,7 @@ pub mod rmrk_example_equippable {
                 .add_asset_entry(
                     ASSET_ID1,
                     EQUIPPABLE_GROUP_ID,
+                    None,
                     String::from(ASSET_URI1),
                     vec![]
                 )
@@ -567,13 +818,13 @@ pub mod rmrk_example_equippable {
 
             // reject adding asset with same asset_id
             assert_eq!(
-                rmrk.add_asset_entry(ASSET_ID1, 1, String::from(ASSET_URI1), vec![]),
+                rmrk.add_asset_entry(ASSET_ID1, 1, None, String::from(ASSET_URI1), vec![]),
                 Err(RmrkError::AssetIdAlreadyExists.into())
             );
 
             // add one more asset
             assert!(rmrk
-                .add_asset_entry(ASSET_ID2, 0, String::from(ASSET_URI2), vec![])
+                .add_asset_entry(ASSET_ID2, 0, None, String::from(ASSET_URI2), vec![])
                 .is_ok());
             assert_eq!(rmrk.total_assets(), 2);
             assert_eq!(
@@ -583,125 +834,782 @@ pub mod rmrk_example_equippable {
         }
 
         #[ink::test]
-        fn add_asset_to_token_works() {
-            let accounts = default_accounts();
+        fn add_asset_entry_validates_parts() {
             const ASSET_URI: &str = "asset_uri/";
-            const ASSET_ID: AssetId = 1;
-            const TOKEN_ID1: Id = Id::U64(1);
-            const TOKEN_ID2: Id = Id::U64(2);
 
             let mut rmrk = init();
-            // Add new asset entry
+            assert_eq!(
+                rmrk.add_asset_entry(1, 0, None, String::from(ASSET_URI), vec![0]),
+                Err(RmrkError::UnknownPartId.into())
+            );
+            assert_eq!(
+                rmrk.add_asset_entry_auto(0, None, String::from(ASSET_URI), vec![0]),
+                Err(RmrkError::UnknownPartId.into())
+            );
+
+            // parts of the local base, referenced implicitly or by this contract's address
+            assert!(rmrk.add_part_list(fixed_parts(2)).is_ok());
             assert!(rmrk
-                .add_asset_entry(ASSET_ID, 1, String::from(ASSET_URI), vec![])
+                .add_asset_entry(1, 0, None, String::from(ASSET_URI), vec![0, 1])
                 .is_ok());
-            assert_eq!(rmrk.total_assets(), 1);
-            assert_eq!(1, ink_env::test::recorded_events().count());
+            let this_contract = ink_env::account_id::<Environment>();
+            assert!(rmrk
+                .add_asset_entry(2, 0, Some(this_contract), String::from(ASSET_URI), vec![1])
+                .is_ok());
+            assert_eq!(rmrk.get_asset_base(2), Some(this_contract));
+            assert_eq!(
+                rmrk.add_asset_entry(3, 0, None, String::from(ASSET_URI), vec![1, 2]),
+                Err(RmrkError::UnknownPartId.into())
+            );
+            assert_eq!(rmrk.total_assets(), 2);
+        }
 
-            // mint token and add asset to it. Should be accepted without approval
-            assert!(rmrk.mint(accounts.alice).is_ok());
-            assert_eq!(2, ink_env::test::recorded_events().count());
-            assert!(rmrk.add_asset_to_token(TOKEN_ID1, ASSET_ID, None).is_ok());
-            assert_eq!(4, ink_env::test::recorded_events().count());
-            assert_eq!(rmrk.total_token_assets(TOKEN_ID1), Ok((1, 0)));
+        #[ink::test]
+        fn add_asset_entry_auto_works() {
+            const ASSET_URI: &str = "asset_uri/";
+            const IMPORTED_ASSET_ID: AssetId = 5;
 
-            // error cases
+            let mut rmrk = init();
             assert_eq!(
-                rmrk.add_asset_to_token(TOKEN_ID1, ASSET_ID, None),
-                Err(RmrkError::AlreadyAddedAsset.into())
+                rmrk.add_asset_entry_auto(0, None, String::from(ASSET_URI), vec![]),
+                Ok(1)
             );
             assert_eq!(
-                rmrk.add_asset_to_token(TOKEN_ID1, 42, None),
-                Err(RmrkError::AssetIdNotFound.into())
+                rmrk.add_asset_entry_auto(0, None, String::from(ASSET_URI), vec![]),
+                Ok(2)
             );
 
-            // mint second token to non owner (Bob)
-            set_sender(accounts.alice);
-            assert!(rmrk.mint(accounts.bob).is_ok());
-            assert_eq!(5, ink_env::test::recorded_events().count());
+            // explicit id moves the counter past it
+            assert!(rmrk
+                .add_asset_entry(IMPORTED_ASSET_ID, 0, None, String::from(ASSET_URI), vec![])
+                .is_ok());
+            assert_eq!(
+                rmrk.add_asset_entry_auto(0, None, String::from(ASSET_URI), vec![]),
+                Ok(6)
+            );
+            assert_eq!(rmrk.total_assets(), 4);
+            assert_eq!(rmrk.get_asset_uri(6), Some(String::from(ASSET_URI)));
 
-            // Add asset by alice and reject asset by Bob to test asset_reject
-            set_sender(accounts.alice);
-            assert!(rmrk.add_asset_to_token(TOKEN_ID2, ASSET_ID, None).is_ok());
-            assert_eq!(6, ink_env::test::recorded_events().count());
-            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((0, 1)));
-            set_sender(accounts.bob);
-            assert!(rmrk.reject_asset(TOKEN_ID2, ASSET_ID).is_ok());
-            assert_eq!(7, ink_env::test::recorded_events().count());
-            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((0, 0)));
+            // explicit id below the counter still works if it is free
+            assert!(rmrk
+                .add_asset_entry(3, 0, None, String::from(ASSET_URI), vec![])
+                .is_ok());
+            assert_eq!(
+                rmrk.add_asset_entry_auto(0, None, String::from(ASSET_URI), vec![]),
+                Ok(7)
+            );
 
-            // Add asset by alice and accept asset by Bob, to test accept_asset
-            set_sender(accounts.alice);
-            assert!(rmrk.add_asset_to_token(TOKEN_ID2, ASSET_ID, None).is_ok());
-            assert_eq!(8, ink_env::test::recorded_events().count());
-            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((0, 1)));
-            set_sender(accounts.bob);
-            assert!(rmrk.accept_asset(TOKEN_ID2, ASSET_ID).is_ok());
-            assert_eq!(9, ink_env::test::recorded_events().count());
-            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((1, 0)));
-            assert_eq!(rmrk.get_accepted_token_assets(TOKEN_ID2), Ok(Some(vec![1])));
+            // no id left after the highest one
+            assert!(rmrk
+                .add_asset_entry(AssetId::MAX, 0, None, String::from(ASSET_URI), vec![])
+                .is_ok());
+            assert_eq!(
+                rmrk.add_asset_entry_auto(0, None, String::from(ASSET_URI), vec![]),
+                Err(RmrkError::AssetIdsExhausted.into())
+            );
+            assert_eq!(rmrk.total_assets(), 7);
 
-            // Try adding asset to not minted token fails
-            set_sender(accounts.alice);
+            set_sender(default_accounts().bob);
             assert_eq!(
-                rmrk.add_asset_to_token(Id::U64(3), ASSET_ID, None),
-                Err(TokenNotExists.into())
+                rmrk.add_asset_entry_auto(0, None, String::from(ASSET_URI), vec![]),
+                Err(MissingRole.into())
             );
+        }
 
-            // Try removing not added asset fails
+        #[ink::test]
+        fn get_asset_works() {
+            const ASSET_URI: &str = "asset_uri/";
+            const EQUIPPABLE_GROUP_ID: EquippableGroupId = 3;
+
+            let mut rmrk = init();
+            assert!(rmrk.add_part_list(fixed_parts(6)).is_ok());
+            assert_eq!(rmrk.get_asset(1), None);
+            for asset_id in 1..=5 {
+                assert!(rmrk
+                    .add_asset_entry(
+                        asset_id,
+                        EQUIPPABLE_GROUP_ID,
+                        None,
+                        String::from(ASSET_URI),
+                        vec![asset_id, 0]
+                    )
+                    .is_ok());
+            }
+
+            let asset = rmrk.get_asset(2).unwrap();
+            assert_eq!(asset.equippable_group_id, EQUIPPABLE_GROUP_ID);
+            assert_eq!(asset.asset_uri, String::from(ASSET_URI));
+            assert_eq!(asset.part_ids, vec![2, 0]);
+            assert_eq!(rmrk.get_asset_base(2), None);
+
+            assert_eq!(rmrk.get_collection_asset_ids(0, 2), vec![1, 2]);
+            assert_eq!(rmrk.get_collection_asset_ids(3, 10), vec![4, 5]);
+            assert_eq!(rmrk.get_collection_asset_ids(5, 10), vec![]);
+        }
+
+        #[ink::test]
+        fn update_and_remove_asset_entries_works() {
+            let accounts = default_accounts();
+            const ASSET_URI: &str = "asset_uri/";
+            const FIXED_ASSET_URI: &str = "fixed_asset_uri/";
+            const TOKEN_ID: Id = Id::U64(1);
+
+            let mut rmrk = init();
+            for asset_id in 1..=3 {
+                assert!(rmrk
+                    .add_asset_entry(asset_id, 1, None, String::from(ASSET_URI), vec![])
+                    .is_ok());
+            }
+            assert!(rmrk.mint(accounts.bob).is_ok());
+            assert!(rmrk.add_asset_to_token(TOKEN_ID, 1, None).is_ok());
+
+            // update uri
+            assert!(rmrk.set_asset_uri(1, String::from(FIXED_ASSET_URI)).is_ok());
+            assert_eq!(rmrk.get_asset_uri(1), Some(String::from(FIXED_ASSET_URI)));
+
+            // deprecated asset can't be added to tokens
+            assert!(rmrk.deprecate_asset(2).is_ok());
+            assert!(rmrk.is_asset_deprecated(2));
             assert_eq!(
-                rmrk.remove_asset(TOKEN_ID2, 42),
-                Err(RmrkError::AssetIdNotFound.into())
+                rmrk.add_asset_to_token(TOKEN_ID, 2, None),
+                Err(RmrkError::AssetIsDeprecated.into())
             );
 
-            // Try removing asset for not minted token fails
+            // only unused assets can be removed
             assert_eq!(
-                rmrk.remove_asset(Id::U64(3), ASSET_ID),
-                Err(TokenNotExists.into())
+                rmrk.remove_asset_entry(1),
+                Err(RmrkError::AssetInUse.into())
             );
+            assert!(rmrk.remove_asset_entry(2).is_ok());
+            assert_eq!(rmrk.get_asset(2), None);
+            assert!(!rmrk.is_asset_deprecated(2));
+            assert_eq!(rmrk.get_collection_asset_ids(0, 10), vec![1, 3]);
+            assert_eq!(rmrk.total_assets(), 2);
 
-            // Try removing asset by collection owner fails
+            set_sender(accounts.bob);
+            assert!(rmrk.reject_asset(TOKEN_ID, 1).is_ok());
             set_sender(accounts.alice);
+            assert!(rmrk.remove_asset_entry(1).is_ok());
+            assert_eq!(rmrk.get_collection_asset_ids(0, 10), vec![3]);
+
+            // error cases
             assert_eq!(
-                rmrk.remove_asset(TOKEN_ID2, ASSET_ID),
-                Err(RmrkError::NotTokenOwner.into())
+                rmrk.set_asset_uri(42, String::from(ASSET_URI)),
+                Err(RmrkError::AssetIdNotFound.into())
+            );
+            assert_eq!(
+                rmrk.remove_asset_entry(42),
+                Err(RmrkError::AssetIdNotFound.into())
             );
-
-            // Remove accepted asset
             set_sender(accounts.bob);
-            assert!(rmrk.remove_asset(TOKEN_ID2, ASSET_ID).is_ok());
-            assert_eq!(10, ink_env::test::recorded_events().count());
-            assert_eq!(rmrk.get_accepted_token_assets(TOKEN_ID2), Ok(Some(vec![])));
-            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((0, 0)));
+            assert_eq!(rmrk.deprecate_asset(3), Err(MissingRole.into()));
         }
 
         #[ink::test]
-        fn add_asset_to_token_with_replace_works() {
+        fn burn_releases_token_assets_works() {
             let accounts = default_accounts();
-            const ASSET_URI1: &str = "asset_uri/1";
-            const ASSET_URI2: &str = "asset_uri/2";
-            const ASSET_URI3: &str = "asset_uri/3";
-            const ASSET_ID1: AssetId = 1;
-            const ASSET_ID2: AssetId = 2;
-            const ASSET_ID3: AssetId = 3;
+            const ASSET_URI: &str = "asset_uri/";
             const TOKEN_ID: Id = Id::U64(1);
+            const SHARED_ASSET_ID: AssetId = 1;
+            const OWN_ASSET_ID: AssetId = 2;
 
             let mut rmrk = init();
-            // Add new asset entry
+            for asset_id in [SHARED_ASSET_ID, OWN_ASSET_ID] {
+                assert!(rmrk
+                    .add_asset_entry(asset_id, 1, None, String::from(ASSET_URI), vec![])
+                    .is_ok());
+            }
+            assert!(rmrk.mint(accounts.bob).is_ok());
+            assert!(rmrk.mint(accounts.bob).is_ok());
+            // the shared asset is pending on both tokens, the other one is accepted on token 1
             assert!(rmrk
-                .add_asset_entry(ASSET_ID1, 0, String::from(ASSET_URI1), vec![])
+                .add_asset_to_token(TOKEN_ID, SHARED_ASSET_ID, None)
                 .is_ok());
             assert!(rmrk
-                .add_asset_entry(ASSET_ID2, 0, String::from(ASSET_URI2), vec![])
+                .add_asset_to_token(Id::U64(2), SHARED_ASSET_ID, None)
                 .is_ok());
             assert!(rmrk
-                .add_asset_entry(ASSET_ID3, 0, String::from(ASSET_URI3), vec![])
+                .add_asset_to_token(TOKEN_ID, OWN_ASSET_ID, None)
                 .is_ok());
+            set_sender(accounts.bob);
+            assert!(rmrk.accept_asset(TOKEN_ID, OWN_ASSET_ID).is_ok());
 
-            assert_eq!(rmrk.total_assets(), 3);
-
-            // mint token and add asset to it. Should be accepted without approval
-            assert!(rmrk.mint(accounts.alice).is_ok());
+            assert!(openbrush::contracts::psp34::Internal::_burn_from(
+                &mut rmrk,
+                accounts.bob,
+                TOKEN_ID
+            )
+            .is_ok());
+            assert_eq!(rmrk.multiasset.pending_assets.get(TOKEN_ID), None);
+            assert_eq!(rmrk.multiasset.accepted_assets.get(TOKEN_ID), None);
+            assert_eq!(rmrk.multiasset.asset_usage.get(SHARED_ASSET_ID), Some(1));
+            assert_eq!(rmrk.multiasset.asset_usage.get(OWN_ASSET_ID), Some(0));
+
+            // asset entries used only by the burned token can be removed
+            set_sender(accounts.alice);
+            assert!(rmrk.remove_asset_entry(OWN_ASSET_ID).is_ok());
+            assert_eq!(
+                rmrk.remove_asset_entry(SHARED_ASSET_ID),
+                Err(RmrkError::AssetInUse.into())
+            );
+        }
+
+        #[ink::test]
+        fn storage_migration_works() {
+            let accounts = default_accounts();
+            const ASSET_URI: &str = "asset_uri/";
+
+            const LEGACY_ASSET_ID: AssetId = 7;
+            const LEGACY_PART_ID: PartId = 1;
+
+            let mut rmrk = init();
+            // contract deployed before ids were indexed
+            rmrk.multiasset.collection_asset_ids = vec![LEGACY_ASSET_ID, 8, 9];
+            rmrk.multiasset.collection_asset_entries.insert(
+                LEGACY_ASSET_ID,
+                &Asset {
+                    part_ids: vec![LEGACY_PART_ID],
+                    ..Default::default()
+                },
+            );
+            rmrk.base.part_ids = vec![0, 1, 2];
+            assert!(rmrk
+                .add_asset_entry(1, 1, None, String::from(ASSET_URI), vec![])
+                .is_ok());
+            assert_eq!(rmrk.total_assets(), 4);
+            assert_eq!(rmrk.get_collection_asset_ids(0, 10), vec![1, 7, 8, 9]);
+            assert_eq!(rmrk.get_collection_asset_ids(2, 1), vec![8]);
+            assert_eq!(rmrk.multiasset.next_asset_id, 2);
+            assert_eq!(rmrk.get_parts_count(), 3);
+            // usage of entries added before it was counted is unknown
+            assert_eq!(
+                rmrk.remove_asset_entry(LEGACY_ASSET_ID),
+                Err(RmrkError::AssetInUse.into())
+            );
+            // parts of entries which aren't migrated are still referenced
+            assert_eq!(
+                rmrk.ensure_part_unreferenced(LEGACY_PART_ID),
+                Err(RmrkError::PartInUse.into())
+            );
+
+            set_sender(accounts.bob);
+            assert_eq!(
+                rmrk.migrate_collection_asset_ids(2),
+                Err(MissingRole.into())
+            );
+            assert_eq!(rmrk.migrate_part_ids(2), Err(MissingRole.into()));
+
+            set_sender(accounts.alice);
+            assert_eq!(rmrk.migrate_collection_asset_ids(2), Ok(1));
+            assert_eq!(rmrk.get_collection_asset_ids(0, 10), vec![1, 7, 8, 9]);
+            assert_eq!(rmrk.migrate_collection_asset_ids(10), Ok(0));
+            assert!(rmrk.multiasset.collection_asset_ids.is_empty());
+            assert_eq!(rmrk.multiasset.next_asset_id, 10);
+            assert_eq!(rmrk.total_assets(), 4);
+            assert_eq!(rmrk.get_collection_asset_ids(0, 10), vec![1, 7, 8, 9]);
+            assert_eq!(
+                rmrk.remove_asset_entry(LEGACY_ASSET_ID),
+                Err(RmrkError::AssetInUse.into())
+            );
+            assert!(rmrk.remove_asset_entry(1).is_ok());
+            assert_eq!(
+                rmrk.add_asset_entry_auto(0, None, String::from(ASSET_URI), vec![]),
+                Ok(10)
+            );
+            assert_eq!(rmrk.multiasset.part_references.get(LEGACY_PART_ID), Some(1));
+            assert_eq!(
+                rmrk.ensure_part_unreferenced(LEGACY_PART_ID),
+                Err(RmrkError::PartInUse.into())
+            );
+
+            assert_eq!(rmrk.migrate_part_ids(10), Ok(0));
+            assert!(rmrk.base.part_ids.is_empty());
+            assert_eq!(rmrk.get_parts_count(), 3);
+            assert_eq!(rmrk.get_part_ids(0, 10), vec![0, 1, 2]);
+        }
+
+        #[ink::test]
+        fn migrate_children_works() {
+            let accounts = default_accounts();
+            let mut rmrk = init();
+            for _ in 0..3 {
+                assert!(rmrk.mint(accounts.alice).is_ok());
+            }
+
+            // parents 1 and 3 hold children in the previous storage layout
+            let child_nft = |token_id| (accounts.charlie, Id::U64(token_id));
+            rmrk.nesting
+                .legacy_accepted_children
+                .insert(Id::U64(1), &vec![child_nft(1), child_nft(2)]);
+            rmrk.nesting
+                .legacy_pending_children
+                .insert(Id::U64(3), &vec![child_nft(3)]);
+            assert_eq!(
+                rmrk.get_unmigrated_parents(0, 10),
+                vec![Id::U64(1), Id::U64(3)]
+            );
+            assert_eq!(rmrk.get_unmigrated_parents(1, 1), vec![]);
+            // children of parents which aren't migrated are still nested
+            assert_eq!(
+                rmrk.reclaim_child(child_nft(3), accounts.alice),
+                Err(RmrkError::ChildIsNested.into())
+            );
+
+            set_sender(accounts.bob);
+            assert_eq!(
+                rmrk.migrate_children(vec![Id::U64(1)]),
+                Err(MissingRole.into())
+            );
+
+            set_sender(accounts.alice);
+            assert!(rmrk.migrate_children(vec![Id::U64(1)]).is_ok());
+            assert_eq!(rmrk.get_unmigrated_parents(0, 10), vec![Id::U64(3)]);
+            assert!(rmrk.migrate_children(vec![Id::U64(3)]).is_ok());
+            assert_eq!(rmrk.get_unmigrated_parents(0, 10), vec![]);
+            assert_eq!(rmrk.children_balance(Id::U64(1)), Ok((2, 0)));
+            assert_eq!(rmrk.children_balance(Id::U64(3)), Ok((0, 1)));
+            assert_eq!(rmrk.get_parent_of_child(child_nft(2)), Some(Id::U64(1)));
+        }
+
+        #[ink::test]
+        fn token_asset_uri_works() {
+            let accounts = default_accounts();
+            const ASSET_URI: &str = "asset_uri/";
+            const TOKEN_ASSET_URI: &str = "token_asset_uri/";
+            const OPERATOR_ASSET_URI: &str = "operator_asset_uri/";
+            const TOKEN_ID1: Id = Id::U64(1);
+            const TOKEN_ID2: Id = Id::U64(2);
+
+            let mut rmrk = init();
+            for asset_id in 1..=2 {
+                assert!(rmrk
+                    .add_asset_entry(asset_id, 1, None, String::from(ASSET_URI), vec![])
+                    .is_ok());
+            }
+            assert!(rmrk.mint(accounts.bob).is_ok());
+            assert!(rmrk.mint(accounts.bob).is_ok());
+            assert!(rmrk.add_asset_to_token(TOKEN_ID1, 1, None).is_ok());
+            assert!(rmrk.add_asset_to_token(TOKEN_ID2, 1, None).is_ok());
+
+            // contributor sets the uri of a pending asset for one token only
+            assert!(rmrk
+                .set_token_asset_uri(TOKEN_ID1, 1, Some(String::from(TOKEN_ASSET_URI)))
+                .is_ok());
+            assert_eq!(
+                rmrk.get_token_asset_uri(TOKEN_ID1, 1),
+                Some(String::from(TOKEN_ASSET_URI))
+            );
+            assert_eq!(
+                rmrk.get_token_asset_uri(TOKEN_ID2, 1),
+                Some(String::from(ASSET_URI))
+            );
+            assert_eq!(rmrk.get_asset_uri(1), Some(String::from(ASSET_URI)));
+            // asset which isn't on the token has no uri for it
+            assert_eq!(rmrk.get_token_asset_uri(TOKEN_ID1, 2), None);
+
+            // approved operator can set it too, owner without role can't
+            set_sender(accounts.bob);
+            assert_eq!(
+                rmrk.set_token_asset_uri(TOKEN_ID2, 1, Some(String::from(OPERATOR_ASSET_URI))),
+                Err(MissingRole.into())
+            );
+            assert!(rmrk
+                .approve_for_assets(Some(accounts.charlie), TOKEN_ID2)
+                .is_ok());
+            set_sender(accounts.charlie);
+            assert!(rmrk
+                .set_token_asset_uri(TOKEN_ID2, 1, Some(String::from(OPERATOR_ASSET_URI)))
+                .is_ok());
+            assert_eq!(
+                rmrk.get_token_asset_uri(TOKEN_ID2, 1),
+                Some(String::from(OPERATOR_ASSET_URI))
+            );
+            assert_eq!(
+                rmrk.set_token_asset_uri(TOKEN_ID1, 1, None),
+                Err(MissingRole.into())
+            );
+
+            // asset must be on the token
+            set_sender(accounts.alice);
+            assert_eq!(
+                rmrk.set_token_asset_uri(TOKEN_ID1, 2, Some(String::from(TOKEN_ASSET_URI))),
+                Err(RmrkError::AssetIdNotFound.into())
+            );
+            assert_eq!(
+                rmrk.set_token_asset_uri(Id::U64(42), 1, None),
+                Err(TokenNotExists.into())
+            );
+
+            // clearing restores the asset uri
+            assert!(rmrk.set_token_asset_uri(TOKEN_ID2, 1, None).is_ok());
+            assert_eq!(
+                rmrk.get_token_asset_uri(TOKEN_ID2, 1),
+                Some(String::from(ASSET_URI))
+            );
+
+            // rejecting the asset drops the token uri
+            set_sender(accounts.bob);
+            assert!(rmrk.reject_asset(TOKEN_ID1, 1).is_ok());
+            set_sender(accounts.alice);
+            assert!(rmrk.add_asset_to_token(TOKEN_ID1, 1, None).is_ok());
+            assert_eq!(
+                rmrk.get_token_asset_uri(TOKEN_ID1, 1),
+                Some(String::from(ASSET_URI))
+            );
+            assert_eq!(rmrk.get_token_asset_uri(TOKEN_ID1, 42), None);
+        }
+
+        #[ink::test]
+        fn typed_assets_work() {
+            const ASSET_URI: &str = "asset_uri/";
+            const THUMBNAIL_URI: &str = "thumbnail_uri/";
+            const TOKEN_ID: Id = Id::U64(1);
+
+            let accounts = default_accounts();
+            let mut rmrk = init();
+            for asset_id in 1..=4 {
+                assert!(rmrk
+                    .add_asset_entry(asset_id, 0, None, String::from(ASSET_URI), vec![])
+                    .is_ok());
+            }
+            assert!(rmrk.set_asset_type(1, AssetMediaType::Image, None).is_ok());
+            assert!(rmrk
+                .set_asset_type(2, AssetMediaType::Audio, Some(String::from(THUMBNAIL_URI)))
+                .is_ok());
+            assert!(rmrk.set_asset_type(3, AssetMediaType::Image, None).is_ok());
+            assert_eq!(
+                rmrk.get_asset_type(2),
+                Some(TypedAsset {
+                    media_type: AssetMediaType::Audio,
+                    thumbnail_uri: Some(String::from(THUMBNAIL_URI)),
+                })
+            );
+            assert_eq!(rmrk.get_asset_type(4), None);
+
+            assert!(rmrk.mint(accounts.alice).is_ok());
+            for asset_id in 1..=4 {
+                assert!(rmrk.add_asset_to_token(TOKEN_ID, asset_id, None).is_ok());
+            }
+            assert_eq!(
+                rmrk.get_accepted_assets_of_type(TOKEN_ID, AssetMediaType::Image),
+                Ok(vec![1, 3])
+            );
+            assert_eq!(
+                rmrk.get_top_asset_of_type(TOKEN_ID, AssetMediaType::Image),
+                Ok(Some(1))
+            );
+            assert!(rmrk.set_priority(TOKEN_ID, vec![3, 2, 1, 0]).is_ok());
+            assert_eq!(
+                rmrk.get_top_asset_of_type(TOKEN_ID, AssetMediaType::Image),
+                Ok(Some(3))
+            );
+            assert_eq!(
+                rmrk.get_top_asset_of_type(TOKEN_ID, AssetMediaType::Model),
+                Ok(None)
+            );
+
+            // error cases
+            assert_eq!(
+                rmrk.set_asset_type(42, AssetMediaType::Image, None),
+                Err(RmrkError::AssetIdNotFound.into())
+            );
+            set_sender(accounts.bob);
+            assert_eq!(
+                rmrk.set_asset_type(4, AssetMediaType::Image, None),
+                Err(MissingRole.into())
+            );
+        }
+
+        #[ink::test]
+        fn add_asset_to_token_works() {
+            let accounts = default_accounts();
+            const ASSET_URI: &str = "asset_uri/";
+            const ASSET_ID: AssetId = 1;
+            const TOKEN_ID1: Id = Id::U64(1);
+            const TOKEN_ID2: Id = Id::U64(2);
+
+            let mut rmrk = init();
+            // Add new asset entry
+            assert!(rmrk
+                .add_asset_entry(ASSET_ID, 1, None, String::from(ASSET_URI), vec![])
+                .is_ok());
+            assert_eq!(rmrk.total_assets(), 1);
+            assert_eq!(1, ink_env::test::recorded_events().count());
+
+            // mint token and add asset to it. Should be accepted without approval
+            assert!(rmrk.mint(accounts.alice).is_ok());
+            assert_eq!(2, ink_env::test::recorded_events().count());
+            assert!(rmrk.add_asset_to_token(TOKEN_ID1, ASSET_ID, None).is_ok());
+            assert_eq!(4, ink_env::test::recorded_events().count());
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID1), Ok((1, 0)));
+
+            // error cases
+            assert_eq!(
+                rmrk.add_asset_to_token(TOKEN_ID1, ASSET_ID, None),
+                Err(RmrkError::AlreadyAddedAsset.into())
+            );
+            assert_eq!(
+                rmrk.add_asset_to_token(TOKEN_ID1, 42, None),
+                Err(RmrkError::AssetIdNotFound.into())
+            );
+
+            // mint second token to non owner (Bob)
+            set_sender(accounts.alice);
+            assert!(rmrk.mint(accounts.bob).is_ok());
+            assert_eq!(5, ink_env::test::recorded_events().count());
+
+            // Add asset by alice and reject asset by Bob to test asset_reject
+            set_sender(accounts.alice);
+            assert!(rmrk.add_asset_to_token(TOKEN_ID2, ASSET_ID, None).is_ok());
+            assert_eq!(6, ink_env::test::recorded_events().count());
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((0, 1)));
+            assert_eq!(
+                rmrk.get_pending_token_assets(TOKEN_ID2),
+                Ok(Some(vec![ASSET_ID]))
+            );
+            set_sender(accounts.bob);
+            assert!(rmrk.reject_asset(TOKEN_ID2, ASSET_ID).is_ok());
+            assert_eq!(7, ink_env::test::recorded_events().count());
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((0, 0)));
+
+            // Add asset by alice and accept asset by Bob, to test accept_asset
+            set_sender(accounts.alice);
+            assert!(rmrk.add_asset_to_token(TOKEN_ID2, ASSET_ID, None).is_ok());
+            assert_eq!(8, ink_env::test::recorded_events().count());
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((0, 1)));
+            set_sender(accounts.bob);
+            assert!(rmrk.accept_asset(TOKEN_ID2, ASSET_ID).is_ok());
+            assert_eq!(9, ink_env::test::recorded_events().count());
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((1, 0)));
+            assert_eq!(rmrk.get_accepted_token_assets(TOKEN_ID2), Ok(Some(vec![1])));
+            assert_eq!(rmrk.get_pending_token_assets(TOKEN_ID2), Ok(Some(vec![])));
+
+            // Try adding asset to not minted token fails
+            set_sender(accounts.alice);
+            assert_eq!(
+                rmrk.add_asset_to_token(Id::U64(3), ASSET_ID, None),
+                Err(TokenNotExists.into())
+            );
+
+            // Try removing not added asset fails
+            assert_eq!(
+                rmrk.remove_asset(TOKEN_ID2, 42),
+                Err(RmrkError::AssetIdNotFound.into())
+            );
+
+            // Try removing asset for not minted token fails
+            assert_eq!(
+                rmrk.remove_asset(Id::U64(3), ASSET_ID),
+                Err(TokenNotExists.into())
+            );
+
+            // Try removing asset by collection owner fails
+            set_sender(accounts.alice);
+            assert_eq!(
+                rmrk.remove_asset(TOKEN_ID2, ASSET_ID),
+                Err(RmrkError::NotTokenOwner.into())
+            );
+
+            // Remove accepted asset
+            set_sender(accounts.bob);
+            assert!(rmrk.remove_asset(TOKEN_ID2, ASSET_ID).is_ok());
+            assert_eq!(10, ink_env::test::recorded_events().count());
+            assert_eq!(rmrk.get_accepted_token_assets(TOKEN_ID2), Ok(Some(vec![])));
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((0, 0)));
+        }
+
+        #[ink::test]
+        fn add_asset_to_tokens_works() {
+            let accounts = default_accounts();
+            const ASSET_URI: &str = "asset_uri/";
+            const ASSET_ID: AssetId = 1;
+
+            let mut rmrk = init();
+            assert!(rmrk
+                .add_asset_entry(ASSET_ID, 1, None, String::from(ASSET_URI), vec![])
+                .is_ok());
+            assert!(rmrk.mint_many(accounts.bob, 3).is_ok());
+            assert!(rmrk.add_asset_to_token(Id::U64(1), ASSET_ID, None).is_ok());
+
+            // tokens which can't receive the asset are reported, the rest receive it
+            let token_ids = vec![Id::U64(1), Id::U64(2), Id::U64(3), Id::U64(4)];
+            assert_eq!(
+                rmrk.add_asset_to_tokens(ASSET_ID, token_ids.clone(), None),
+                Ok(vec![
+                    (Id::U64(1), Err(RmrkError::AddingPendingAsset.into())),
+                    (Id::U64(2), Ok(())),
+                    (Id::U64(3), Ok(())),
+                    (Id::U64(4), Err(TokenNotExists.into())),
+                ])
+            );
+            for token_id in 1..=3 {
+                assert_eq!(rmrk.total_token_assets(Id::U64(token_id)), Ok((0, 1)));
+            }
+
+            // error cases
+            assert_eq!(
+                rmrk.add_asset_to_tokens(42, token_ids.clone(), None),
+                Err(RmrkError::AssetIdNotFound.into())
+            );
+            set_sender(accounts.bob);
+            assert_eq!(
+                rmrk.add_asset_to_tokens(ASSET_ID, token_ids, None),
+                Err(MissingRole.into())
+            );
+        }
+
+        #[ink::test]
+        fn asset_approvals_work() {
+            let accounts = default_accounts();
+            const ASSET_URI: &str = "asset_uri/";
+            const TOKEN_ID: Id = Id::U64(1);
+
+            let mut rmrk = init();
+            for asset_id in 1..=4 {
+                assert!(rmrk
+                    .add_asset_entry(asset_id, 1, None, String::from(ASSET_URI), vec![])
+                    .is_ok());
+            }
+            assert!(rmrk.mint(accounts.bob).is_ok());
+            for asset_id in 1..=3 {
+                assert!(rmrk.add_asset_to_token(TOKEN_ID, asset_id, None).is_ok());
+            }
+
+            // charlie can't manage Bob's assets until approved
+            set_sender(accounts.charlie);
+            assert_eq!(
+                rmrk.accept_asset(TOKEN_ID, 1),
+                Err(RmrkError::NotTokenOwner.into())
+            );
+            assert_eq!(
+                rmrk.approve_for_assets(Some(accounts.charlie), TOKEN_ID),
+                Err(RmrkError::NotTokenOwner.into())
+            );
+            set_sender(accounts.bob);
+            assert!(rmrk
+                .approve_for_assets(Some(accounts.charlie), TOKEN_ID)
+                .is_ok());
+            assert_eq!(
+                rmrk.get_approved_for_assets(TOKEN_ID),
+                Ok(Some(accounts.charlie))
+            );
+            set_sender(accounts.charlie);
+            assert!(rmrk.accept_asset(TOKEN_ID, 1).is_ok());
+            assert!(rmrk.reject_asset(TOKEN_ID, 2).is_ok());
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID), Ok((1, 1)));
+
+            // asset approval doesn't allow token transfers
+            assert!(rmrk.transfer(accounts.charlie, TOKEN_ID, vec![]).is_err());
+
+            // revoked approval
+            set_sender(accounts.bob);
+            assert!(rmrk.approve_for_assets(None, TOKEN_ID).is_ok());
+            assert_eq!(rmrk.get_approved_for_assets(TOKEN_ID), Ok(None));
+            set_sender(accounts.charlie);
+            assert_eq!(
+                rmrk.accept_asset(TOKEN_ID, 3),
+                Err(RmrkError::NotTokenOwner.into())
+            );
+
+            // operator approved for all Bob's assets
+            set_sender(accounts.bob);
+            assert!(rmrk
+                .set_approval_for_all_for_assets(accounts.django, true)
+                .is_ok());
+            assert!(rmrk.is_approved_for_all_for_assets(accounts.bob, accounts.django));
+            set_sender(accounts.django);
+            assert!(rmrk.accept_asset(TOKEN_ID, 3).is_ok());
+            assert!(rmrk.set_priority(TOKEN_ID, vec![1, 0]).is_ok());
+            assert!(rmrk.remove_asset(TOKEN_ID, 1).is_ok());
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID), Ok((1, 0)));
+
+            // assets added by an approved operator are accepted like the owner's
+            assert!(rmrk.add_asset_to_token(TOKEN_ID, 4, None).is_ok());
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID), Ok((2, 0)));
+
+            set_sender(accounts.bob);
+            assert!(rmrk
+                .set_approval_for_all_for_assets(accounts.django, false)
+                .is_ok());
+            set_sender(accounts.django);
+            assert_eq!(
+                rmrk.remove_asset(TOKEN_ID, 3),
+                Err(RmrkError::NotTokenOwner.into())
+            );
+        }
+
+        #[ink::test]
+        fn pending_assets_limit_works() {
+            let accounts = default_accounts();
+            const ASSET_URI: &str = "asset_uri/";
+            const TOKEN_ID: Id = Id::U64(1);
+
+            let mut rmrk = init();
+            for asset_id in 1..=4 {
+                assert!(rmrk
+                    .add_asset_entry(asset_id, 1, None, String::from(ASSET_URI), vec![])
+                    .is_ok());
+            }
+            assert!(rmrk.mint(accounts.bob).is_ok());
+            assert_eq!(rmrk.get_max_pending_assets(), 128);
+            assert!(rmrk.set_max_pending_assets(2).is_ok());
+            assert_eq!(rmrk.get_max_pending_assets(), 2);
+
+            // Alice adds assets to Bob's token until the limit is reached
+            assert!(rmrk.add_asset_to_token(TOKEN_ID, 1, None).is_ok());
+            assert!(rmrk.add_asset_to_token(TOKEN_ID, 2, None).is_ok());
+            assert_eq!(
+                rmrk.add_asset_to_token(TOKEN_ID, 3, None),
+                Err(RmrkError::MaxPendingAssetsReached.into())
+            );
+
+            // only the owner can reject all assets, and only as many as expected
+            assert_eq!(
+                rmrk.reject_all_assets(TOKEN_ID, 2),
+                Err(RmrkError::NotTokenOwner.into())
+            );
+            set_sender(accounts.bob);
+            assert_eq!(
+                rmrk.reject_all_assets(TOKEN_ID, 1),
+                Err(RmrkError::UnexpectedNumberOfAssets.into())
+            );
+            assert_eq!(rmrk.reject_all_assets(TOKEN_ID, 2), Ok(2));
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID), Ok((0, 0)));
+
+            // the pending list has room again
+            set_sender(accounts.alice);
+            assert!(rmrk.add_asset_to_token(TOKEN_ID, 3, None).is_ok());
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID), Ok((0, 1)));
+        }
+
+        #[ink::test]
+        fn add_asset_to_token_with_replace_works() {
+            let accounts = default_accounts();
+            const ASSET_URI1: &str = "asset_uri/1";
+            const ASSET_URI2: &str = "asset_uri/2";
+            const ASSET_URI3: &str = "asset_uri/3";
+            const ASSET_ID1: AssetId = 1;
+            const ASSET_ID2: AssetId = 2;
+            const ASSET_ID3: AssetId = 3;
+            const TOKEN_ID: Id = Id::U64(1);
+            const TOKEN_ID2: Id = Id::U64(2);
+
+            let mut rmrk = init();
+            // Add new asset entry
+            assert!(rmrk
+                .add_asset_entry(ASSET_ID1, 0, None, String::from(ASSET_URI1), vec![])
+                .is_ok());
+            assert!(rmrk
+                .add_asset_entry(ASSET_ID2, 0, None, String::from(ASSET_URI2), vec![])
+                .is_ok());
+            assert!(rmrk
+                .add_asset_entry(ASSET_ID3, 0, None, String::from(ASSET_URI3), vec![])
+                .is_ok());
+
+            assert_eq!(rmrk.total_assets(), 3);
+
+            // mint token and add asset to it. Should be accepted without approval
+            assert!(rmrk.mint(accounts.alice).is_ok());
 
             assert_eq!(
                 rmrk.add_asset_to_token(TOKEN_ID, ASSET_ID3, Some(ASSET_ID1)),
@@ -723,6 +1631,36 @@ pub mod rmrk_example_equippable {
                 rmrk.get_accepted_token_assets(TOKEN_ID),
                 Ok(Some(vec![3, 2]))
             );
+            assert_eq!(
+                rmrk.add_asset_to_token(TOKEN_ID, ASSET_ID1, Some(42)),
+                Err(RmrkError::InvalidAssetId.into())
+            );
+
+            // replacement on Bob's token waits for Bob to accept it
+            assert!(rmrk.mint(accounts.bob).is_ok());
+            assert!(rmrk.add_asset_to_token(TOKEN_ID2, ASSET_ID1, None).is_ok());
+            set_sender(accounts.bob);
+            assert!(rmrk.accept_asset(TOKEN_ID2, ASSET_ID1).is_ok());
+            set_sender(accounts.alice);
+            assert!(rmrk
+                .add_asset_to_token(TOKEN_ID2, ASSET_ID2, Some(ASSET_ID1))
+                .is_ok());
+            assert_eq!(
+                rmrk.get_accepted_token_assets(TOKEN_ID2),
+                Ok(Some(vec![ASSET_ID1]))
+            );
+            assert_eq!(
+                rmrk.get_asset_replacement(TOKEN_ID2, ASSET_ID2),
+                Some(ASSET_ID1)
+            );
+            set_sender(accounts.bob);
+            assert!(rmrk.accept_asset(TOKEN_ID2, ASSET_ID2).is_ok());
+            assert_eq!(
+                rmrk.get_accepted_token_assets(TOKEN_ID2),
+                Ok(Some(vec![ASSET_ID2]))
+            );
+            assert_eq!(rmrk.get_asset_replacement(TOKEN_ID2, ASSET_ID2), None);
+            assert_eq!(rmrk.total_token_assets(TOKEN_ID2), Ok((1, 0)));
         }
 
         #[ink::test]
@@ -736,10 +1674,10 @@ pub mod rmrk_example_equippable {
             let mut rmrk = init();
             // Add new asset entry
             assert!(rmrk
-                .add_asset_entry(ASSET_ID1, 1, String::from(ASSET_URI), vec![])
+                .add_asset_entry(ASSET_ID1, 1, None, String::from(ASSET_URI), vec![])
                 .is_ok());
             assert!(rmrk
-                .add_asset_entry(ASSET_ID2, 1, String::from(ASSET_URI), vec![])
+                .add_asset_entry(ASSET_ID2, 1, None, String::from(ASSET_URI), vec![])
                 .is_ok());
             assert_eq!(rmrk.total_assets(), 2);
 
@@ -752,22 +1690,32 @@ pub mod rmrk_example_equippable {
                 rmrk.get_accepted_token_assets(TOKEN_ID1),
                 Ok(Some(vec![ASSET_ID1, ASSET_ID2]))
             );
-            assert!(rmrk
-                .set_priority(TOKEN_ID1, vec![ASSET_ID2, ASSET_ID1])
-                .is_ok());
+            assert_eq!(rmrk.get_active_asset_priorities(TOKEN_ID1), Ok(vec![0, 1]));
+            assert_eq!(rmrk.get_top_asset(TOKEN_ID1), Ok(Some(ASSET_ID1)));
+            assert!(rmrk.set_priority(TOKEN_ID1, vec![10, 5]).is_ok());
+            assert_eq!(rmrk.get_active_asset_priorities(TOKEN_ID1), Ok(vec![10, 5]));
+            assert_eq!(rmrk.get_top_asset(TOKEN_ID1), Ok(Some(ASSET_ID2)));
+            // accepted assets keep their order
             assert_eq!(
                 rmrk.get_accepted_token_assets(TOKEN_ID1),
-                Ok(Some(vec![ASSET_ID2, ASSET_ID1]))
+                Ok(Some(vec![ASSET_ID1, ASSET_ID2]))
             );
 
+            // priorities stay aligned when an asset is removed
+            assert!(rmrk.remove_asset(TOKEN_ID1, ASSET_ID1).is_ok());
+            assert_eq!(rmrk.get_active_asset_priorities(TOKEN_ID1), Ok(vec![5]));
+            assert_eq!(rmrk.get_top_asset(TOKEN_ID1), Ok(Some(ASSET_ID2)));
+
             // error cases
             assert_eq!(
-                rmrk.set_priority(TOKEN_ID1, vec![ASSET_ID2]),
+                rmrk.set_priority(TOKEN_ID1, vec![1, 2]),
                 Err(RmrkError::BadPriorityLength.into())
             );
+            assert_eq!(rmrk.get_top_asset(Id::U64(2)), Ok(None));
+            set_sender(accounts.bob);
             assert_eq!(
-                rmrk.set_priority(TOKEN_ID1, vec![ASSET_ID2, 42]),
-                Err(RmrkError::AssetIdNotFound.into())
+                rmrk.set_priority(TOKEN_ID1, vec![1]),
+                Err(RmrkError::NotTokenOwner.into())
             );
         }
 
@@ -822,6 +1770,8 @@ pub mod rmrk_example_equippable {
             assert!(rmrk.get_parts_count() == 0);
             assert!(rmrk.add_part_list(part_list.clone()).is_ok());
             assert_eq!(rmrk.get_parts_count(), part_list.len() as u32);
+            assert_eq!(rmrk.get_part_ids(0, 10), vec![0, 1]);
+            assert_eq!(rmrk.get_part_ids(1, 10), vec![1]);
             assert_eq!(rmrk.get_part(0).unwrap().z, part_list[0].z);
             assert_eq!(
                 rmrk.get_part(0).unwrap().metadata_uri,
@@ -830,14 +1780,15 @@ pub mod rmrk_example_equippable {
 
             // verify array of equippable addresses
             assert!(rmrk
-                .ensure_equippable(PART_ID0, EQUIPABLE_ADDRESS1.into())
+                .check_slot_equippable(PART_ID0, EQUIPABLE_ADDRESS1.into())
                 .is_ok());
             assert!(rmrk
-                .ensure_equippable(PART_ID0, EQUIPABLE_ADDRESS2.into())
-                .is_ok());
-            assert!(!rmrk
-                .ensure_equippable(PART_ID1, EQUIPABLE_ADDRESS2.into())
+                .check_slot_equippable(PART_ID0, EQUIPABLE_ADDRESS2.into())
                 .is_ok());
+            assert_eq!(
+                rmrk.check_slot_equippable(PART_ID1, EQUIPABLE_ADDRESS2.into()),
+                Err(RmrkError::PartIsNotSlot.into())
+            );
 
             assert!(!rmrk.is_equippable_by_all(PART_ID0));
             assert!(rmrk.set_equippable_by_all(PART_ID0).is_ok());
@@ -846,9 +1797,10 @@ pub mod rmrk_example_equippable {
 
             assert!(rmrk.reset_equippable_addresses(PART_ID0).is_ok());
             assert!(!rmrk.is_equippable_by_all(PART_ID0));
-            assert!(!rmrk
-                .ensure_equippable(PART_ID0, EQUIPABLE_ADDRESS1.into())
-                .is_ok());
+            assert_eq!(
+                rmrk.check_slot_equippable(PART_ID0, EQUIPABLE_ADDRESS1.into()),
+                Err(RmrkError::AddressNotEquippable.into())
+            );
             assert!(rmrk
                 .add_equippable_addresses(
                     PART_ID0,
@@ -856,7 +1808,7 @@ pub mod rmrk_example_equippable {
                 )
                 .is_ok());
             assert!(rmrk
-                .ensure_equippable(PART_ID0, EQUIPABLE_ADDRESS1.into())
+                .check_slot_equippable(PART_ID0, EQUIPABLE_ADDRESS1.into())
                 .is_ok());
             assert_eq!(
                 rmrk.add_equippable_addresses(PART_ID1, vec![EQUIPABLE_ADDRESS1.into()]),
@@ -879,9 +1831,10 @@ pub mod rmrk_example_equippable {
                 Err(RmrkError::BadConfig.into())
             );
 
-            assert!(!rmrk
-                .ensure_equippable(PART_ID0, EQUIPABLE_ADDRESS3.into())
-                .is_ok());
+            assert_eq!(
+                rmrk.check_slot_equippable(PART_ID0, EQUIPABLE_ADDRESS3.into()),
+                Err(RmrkError::AddressNotEquippable.into())
+            );
 
             // verify set/get base metadata
             assert_eq!(rmrk.get_base_metadata(), "");
@@ -893,6 +1846,159 @@ pub mod rmrk_example_equippable {
             // assert_eq!(1, ink_env::test::recorded_events().count());
         }
 
+        #[ink::test]
+        fn check_slot_equippable_works() {
+            const ALLOWED_ADDRESS: [u8; 32] = [1; 32];
+            const OTHER_ADDRESS: [u8; 32] = [2; 32];
+            const ALLOWLIST_SLOT_ID: PartId = 0;
+            const BY_ALL_SLOT_ID: PartId = 1;
+            const FIXED_PART_ID: PartId = 2;
+            let slot = |equippable: Vec<AccountId>, is_equippable_by_all: bool| {
+                Part {
+                    part_type: PartType::Slot,
+                    z: 0,
+                    equippable,
+                    metadata_uri: String::from("ipfs://slots/1.svg"),
+                    is_equippable_by_all,
+                }
+            };
+
+            let mut rmrk = init();
+            let mut parts = vec![
+                slot(vec![ALLOWED_ADDRESS.into()], false),
+                slot(vec![], true),
+            ];
+            parts.extend(fixed_parts(1));
+            assert!(rmrk.add_part_list(parts).is_ok());
+
+            // allowlisted collection
+            assert!(rmrk
+                .check_slot_equippable(ALLOWLIST_SLOT_ID, ALLOWED_ADDRESS.into())
+                .is_ok());
+            assert_eq!(
+                rmrk.check_slot_equippable(ALLOWLIST_SLOT_ID, OTHER_ADDRESS.into()),
+                Err(RmrkError::AddressNotEquippable.into())
+            );
+
+            // slot equippable by all, without allowlist
+            assert!(rmrk
+                .check_slot_equippable(BY_ALL_SLOT_ID, OTHER_ADDRESS.into())
+                .is_ok());
+
+            // fixed part can't be equipped into
+            assert_eq!(
+                rmrk.check_slot_equippable(FIXED_PART_ID, ALLOWED_ADDRESS.into()),
+                Err(RmrkError::PartIsNotSlot.into())
+            );
+
+            // unknown part
+            assert_eq!(
+                rmrk.check_slot_equippable(42, ALLOWED_ADDRESS.into()),
+                Err(RmrkError::UnknownPartId.into())
+            );
+        }
+
+        #[ink::test]
+        fn update_and_remove_parts_works() {
+            const ASSET_URI: &str = "asset_uri/";
+            const ADDRESS1: [u8; 32] = [1; 32];
+            const ADDRESS2: [u8; 32] = [2; 32];
+            const SLOT_ID: PartId = 0;
+            const FIXED_ID1: PartId = 1;
+            const FIXED_ID2: PartId = 2;
+            let slot = Part {
+                part_type: PartType::Slot,
+                z: 0,
+                equippable: vec![ADDRESS1.into(), ADDRESS2.into()],
+                metadata_uri: String::from("ipfs://slots/1.svg"),
+                is_equippable_by_all: false,
+            };
+
+            let accounts = default_accounts();
+            let mut rmrk = init();
+            let mut parts = vec![slot.clone()];
+            parts.extend(fixed_parts(2));
+            assert!(rmrk.add_part_list(parts).is_ok());
+
+            // remove a single equippable address
+            assert!(rmrk
+                .remove_equippable_address(SLOT_ID, ADDRESS1.into())
+                .is_ok());
+            assert_eq!(
+                rmrk.get_part(SLOT_ID).unwrap().equippable,
+                vec![ADDRESS2.into()]
+            );
+            assert_eq!(
+                rmrk.remove_equippable_address(SLOT_ID, ADDRESS1.into()),
+                Err(RmrkError::AddressNotEquippable.into())
+            );
+            assert_eq!(
+                rmrk.remove_equippable_address(FIXED_ID1, ADDRESS2.into()),
+                Err(RmrkError::PartIsNotSlot.into())
+            );
+
+            // update part
+            let mut updated = fixed_parts(1).remove(0);
+            updated.z = 5;
+            updated.metadata_uri = String::from("ipfs://parts/fixed_v2.svg");
+            assert!(rmrk.update_part(FIXED_ID1, updated.clone()).is_ok());
+            let part = rmrk.get_part(FIXED_ID1).unwrap();
+            assert_eq!(part.z, updated.z);
+            assert_eq!(part.metadata_uri, updated.metadata_uri);
+            updated.is_equippable_by_all = true;
+            assert_eq!(
+                rmrk.update_part(FIXED_ID1, updated),
+                Err(RmrkError::BadConfig.into())
+            );
+            assert_eq!(
+                rmrk.update_part(42, slot.clone()),
+                Err(RmrkError::UnknownPartId.into())
+            );
+
+            // parts referenced by an asset entry can't be removed
+            assert!(rmrk
+                .add_asset_entry(1, 0, None, String::from(ASSET_URI), vec![FIXED_ID1])
+                .is_ok());
+            assert_eq!(
+                rmrk.remove_part(FIXED_ID1),
+                Err(RmrkError::PartInUse.into())
+            );
+            // nor change type
+            assert_eq!(
+                rmrk.update_part(FIXED_ID1, slot.clone()),
+                Err(RmrkError::PartInUse.into())
+            );
+            assert_eq!(rmrk.get_part(FIXED_ID1).unwrap().part_type, PartType::Fixed);
+            assert!(rmrk
+                .update_part(FIXED_ID1, fixed_parts(1).remove(0))
+                .is_ok());
+            assert!(rmrk.remove_part(FIXED_ID2).is_ok());
+            assert!(rmrk.get_part(FIXED_ID2).is_none());
+            assert_eq!(rmrk.get_parts_count(), 2);
+            assert_eq!(rmrk.get_part_ids(0, 10), vec![SLOT_ID, FIXED_ID1]);
+
+            assert!(rmrk.remove_asset_entry(1).is_ok());
+            assert!(rmrk.update_part(FIXED_ID1, slot.clone()).is_ok());
+            assert_eq!(rmrk.get_part(FIXED_ID1).unwrap().part_type, PartType::Slot);
+            assert!(rmrk.remove_part(FIXED_ID1).is_ok());
+            assert_eq!(rmrk.get_part_ids(0, 10), vec![SLOT_ID]);
+            assert_eq!(rmrk.remove_part(42), Err(RmrkError::UnknownPartId.into()));
+
+            // parts with a child equipped can't be removed
+            rmrk.equippable.slot_equipments.insert(SLOT_ID, &1u32);
+            assert_eq!(rmrk.remove_part(SLOT_ID), Err(RmrkError::PartInUse.into()));
+            rmrk.equippable.slot_equipments.insert(SLOT_ID, &0u32);
+
+            // only contributors can edit parts
+            set_sender(accounts.bob);
+            assert_eq!(rmrk.update_part(SLOT_ID, slot), Err(MissingRole.into()));
+            assert_eq!(rmrk.remove_part(SLOT_ID), Err(MissingRole.into()));
+            assert_eq!(
+                rmrk.remove_equippable_address(SLOT_ID, ADDRESS2.into()),
+                Err(MissingRole.into())
+            );
+        }
+
         #[ink::test]
         fn equip_works() {
             const ASSET_URI: &str = "asset_uri/";
@@ -937,6 +2043,7 @@ pub mod rmrk_example_equippable {
                 .add_asset_entry(
                     ASSET_ID,
                     EQUIPPABLE_GROUP_ID,
+                    None,
                     String::from(ASSET_URI),
                     vec![PART_ID0]
                 )
@@ -1023,6 +2130,19 @@ pub mod rmrk_example_equippable {
             // check AssetEquipped event is emitted
             // assert_eq!(4, ink_env::test::recorded_events().count());
 
+            // equipped child can't leave the parent token
+            assert_eq!(
+                kanaria.remove_child(TOKEN_ID1, (CHILD_COLLECTION_ADDRESS.into(), CHILD_TOKEN_ID)),
+                Err(RmrkError::ChildIsEquipped.into())
+            );
+
+            // slot with an equipped child can't change type
+            assert_eq!(
+                kanaria.ensure_slot_unequipped(PART_ID0),
+                Err(RmrkError::PartInUse.into())
+            );
+            assert!(kanaria.ensure_slot_unequipped(PART_ID1).is_ok());
+
             // equip fails, TargetAssetCannotReceiveSlot
             assert_eq!(
                 kanaria.equip(
@@ -1050,15 +2170,105 @@ pub mod rmrk_example_equippable {
             // un-equip token
             assert!(kanaria.unequip(TOKEN_ID1, PART_ID0).is_ok());
             assert_eq!(kanaria.get_equipment(TOKEN_ID1, PART_ID0), None);
+            assert!(kanaria.ensure_slot_unequipped(PART_ID0).is_ok());
+            assert!(kanaria
+                .ensure_child_not_equipped(
+                    &TOKEN_ID1,
+                    &(CHILD_COLLECTION_ADDRESS.into(), CHILD_TOKEN_ID)
+                )
+                .is_ok());
+
+            // equip again and un-equip by child
+            assert!(kanaria
+                .equip(
+                    TOKEN_ID1,
+                    ASSET_ID,
+                    PART_ID0,
+                    (CHILD_COLLECTION_ADDRESS.into(), CHILD_TOKEN_ID),
+                    CHILD_ASSET_ID,
+                )
+                .is_ok());
+            assert!(kanaria
+                .unequip_child(
+                    &TOKEN_ID1,
+                    &(CHILD_COLLECTION_ADDRESS.into(), CHILD_TOKEN_ID)
+                )
+                .is_ok());
+            assert_eq!(kanaria.get_equipment(TOKEN_ID1, PART_ID0), None);
+            assert!(kanaria.ensure_slot_unequipped(PART_ID0).is_ok());
 
             // check AssetEquipped event is emitted
             // assert_eq!(6, ink_env::test::recorded_events().count());
         }
 
+        #[ink::test]
+        fn migrate_equipment_works() {
+            let accounts = default_accounts();
+            const TOKEN_ID: Id = Id::U64(1);
+            const SLOT_PART_IDS: [PartId; 2] = [1, 2];
+            let child_nft = (accounts.charlie, Id::U64(5));
+
+            let mut rmrk = init();
+            assert!(rmrk.mint(accounts.alice).is_ok());
+
+            // equipment stored before equipped children were indexed, one child in two slots
+            for slot_part_id in SLOT_PART_IDS {
+                rmrk.equippable.equipment.insert(
+                    (TOKEN_ID, slot_part_id),
+                    &Equipment {
+                        asset_id: 1,
+                        child_asset_id: 2,
+                        child_nft: child_nft.clone(),
+                    },
+                );
+            }
+            assert!(rmrk
+                .ensure_child_not_equipped(&TOKEN_ID, &child_nft)
+                .is_ok());
+
+            set_sender(accounts.bob);
+            assert_eq!(
+                rmrk.migrate_equipment(TOKEN_ID, vec![1, 2]),
+                Err(MissingRole.into())
+            );
+            set_sender(accounts.alice);
+            assert!(rmrk.migrate_equipment(TOKEN_ID, vec![1, 2, 3]).is_ok());
+            assert_eq!(
+                rmrk.ensure_child_not_equipped(&TOKEN_ID, &child_nft),
+                Err(RmrkError::ChildIsEquipped.into())
+            );
+
+            // unequipping one slot keeps the child equipped in the other
+            assert!(rmrk.unequip(TOKEN_ID, 1).is_ok());
+            assert_eq!(
+                rmrk.ensure_child_not_equipped(&TOKEN_ID, &child_nft),
+                Err(RmrkError::ChildIsEquipped.into())
+            );
+            assert!(rmrk.unequip_child(&TOKEN_ID, &child_nft).is_ok());
+            assert_eq!(rmrk.get_equipment(TOKEN_ID, 2), None);
+            assert!(rmrk
+                .ensure_child_not_equipped(&TOKEN_ID, &child_nft)
+                .is_ok());
+        }
+
         fn default_accounts() -> test::DefaultAccounts<ink_env::DefaultEnvironment> {
             test::default_accounts::<Environment>()
         }
 
+        fn fixed_parts(count: usize) -> Vec<Part> {
+            (0..count)
+                .map(|_| {
+                    Part {
+                        part_type: PartType::Fixed,
+                        z: 0,
+                        equippable: vec![],
+                        metadata_uri: String::from("ipfs://parts/fixed.svg"),
+                        is_equippable_by_all: false,
+                    }
+                })
+                .collect()
+        }
+
         fn set_sender(sender: AccountId) {
             ink_env::test::set_caller::<Environment>(sender);
         }
@@ -0,0 +1,58 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// PSP34 collection used in tests as a child contract which reverts transfers on demand.
+#[openbrush::contract]
+pub mod rmrk_example_reverting_child {
+    use ink_storage::traits::SpreadAllocate;
+    use openbrush::{
+        contracts::psp34::*,
+        traits::Storage,
+    };
+
+    // Reverting child contract storage
+    #[ink(storage)]
+    #[derive(Default, SpreadAllocate, Storage)]
+    pub struct RevertingChild {
+        #[storage_field]
+        psp34: psp34::Data,
+        transfers_locked: bool,
+    }
+
+    impl PSP34 for RevertingChild {}
+
+    impl RevertingChild {
+        /// Instantiate new reverting child contract
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            ink_lang::codegen::initialize_contract(|_instance: &mut RevertingChild| {})
+        }
+
+        /// Mint a token to any account
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, id: Id) -> Result<(), PSP34Error> {
+            self._mint_to(to, id)
+        }
+
+        /// While locked, every transfer of an existing token reverts
+        #[ink(message)]
+        pub fn set_transfers_locked(&mut self, locked: bool) {
+            self.transfers_locked = locked;
+        }
+    }
+
+    impl psp34::Internal for RevertingChild {
+        fn _before_token_transfer(
+            &mut self,
+            from: Option<&AccountId>,
+            _to: Option<&AccountId>,
+            _id: &Id,
+        ) -> Result<(), PSP34Error> {
+            assert!(
+                from.is_none() || !self.transfers_locked,
+                "transfers are locked"
+            );
+            Ok(())
+        }
+    }
+}
@@ -133,10 +133,18 @@ describe("RMRK Base tests", () => {
       .tx.addEquippableAddresses(0, [kanaria.address], {
         gasLimit: addEquipGas * 2n,
       });
-    expect((await gem.query.ensureEquippable(0, kanaria.address))?.value).to.be
-      .ok;
-    expect((await gem.query.ensureEquippable(1, kanaria.address))?.value).to.be
-      .ok;
+    expect(
+      (await gem.query.checkSlotEquippable(0, kanaria.address))?.value.err
+    ).to.be.undefined;
+    // slot 0 is equippable by all collections
+    expect((await gem.query.checkSlotEquippable(0, bob.address))?.value.err).to
+      .be.undefined;
+    expect(
+      (await gem.query.checkSlotEquippable(1, kanaria.address))?.value.err.rmrk
+    ).to.be.equal(RmrkError.partIsNotSlot);
+    expect(
+      (await gem.query.checkSlotEquippable(2, kanaria.address))?.value.err.rmrk
+    ).to.be.equal(RmrkError.unknownPartId);
     const removePartListGas = (
       await gem.withSigner(deployer).query.resetEquippableAddresses(0)
     ).gasRequired;
@@ -150,6 +158,85 @@ describe("RMRK Base tests", () => {
       .query.addEquippableAddresses(1, [kanaria.address]);
     expect(failAddEquip.value.err.rmrk).to.be.equal(RmrkError.partIsNotSlot);
   });
+
+  it("Edit and remove parts", async () => {
+    await setup();
+
+    const PART_LIST: Part[] = [
+      {
+        partType: PartType.slot,
+        z: 0,
+        equippable: [kanaria.address],
+        metadataUri: ["ipfs://slots/1.svg"],
+        isEquippableByAll: false,
+      },
+      {
+        partType: PartType.fixed,
+        z: 0,
+        equippable: [],
+        metadataUri: ["ipfs://backgrounds/2.svg"],
+        isEquippableByAll: false,
+      },
+    ];
+    const addPartListGas = (
+      await gem.withSigner(deployer).query.addPartList(PART_LIST)
+    ).gasRequired;
+    await gem
+      .withSigner(deployer)
+      .tx.addPartList(PART_LIST, { gasLimit: addPartListGas * 2n });
+
+    // remove a single equippable address
+    const removeAddressGas = (
+      await gem
+        .withSigner(deployer)
+        .query.removeEquippableAddress(0, kanaria.address)
+    ).gasRequired;
+    const removeAddressResult = await gem
+      .withSigner(deployer)
+      .tx.removeEquippableAddress(0, kanaria.address, {
+        gasLimit: removeAddressGas * 2n,
+      });
+    emit(removeAddressResult, "EquippableAddressRemoved", {
+      part: 0,
+      equippableAddress: kanaria.address,
+    });
+    expect(
+      (await gem.query.checkSlotEquippable(0, kanaria.address))?.value.err.rmrk
+    ).to.be.equal(RmrkError.addressNotEquippable);
+
+    // update a part
+    const updatedPart: Part = { ...PART_LIST[1], z: 5 };
+    const updatePartGas = (
+      await gem.withSigner(deployer).query.updatePart(1, updatedPart)
+    ).gasRequired;
+    const updatePartResult = await gem
+      .withSigner(deployer)
+      .tx.updatePart(1, updatedPart, { gasLimit: updatePartGas * 2n });
+    emit(updatePartResult, "PartUpdated", { part: 1 });
+    expect((await gem.query.getPart(1))?.value.z).to.be.equal(5);
+
+    // parts referenced by an asset entry can't be removed
+    const addAssetGas = (
+      await gem
+        .withSigner(deployer)
+        .query.addAssetEntry(1, 0, null, ["ipfs://gems/full.svg"], [1])
+    ).gasRequired;
+    await gem
+      .withSigner(deployer)
+      .tx.addAssetEntry(1, 0, null, ["ipfs://gems/full.svg"], [1], {
+        gasLimit: addAssetGas * 2n,
+      });
+    const failRemovePart = await gem.withSigner(deployer).query.removePart(1);
+    expect(failRemovePart.value.err.rmrk).to.be.equal(RmrkError.partInUse);
+
+    const removePartGas = (await gem.withSigner(deployer).query.removePart(0))
+      .gasRequired;
+    const removePartResult = await gem
+      .withSigner(deployer)
+      .tx.removePart(0, { gasLimit: removePartGas * 2n });
+    emit(removePartResult, "PartRemoved", { part: 0 });
+    expect((await gem.query.getPartsCount())?.value).to.be.equal(1);
+  });
 });
 
 // Helper function to parse Events
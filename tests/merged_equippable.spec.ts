@@ -4,10 +4,13 @@ import { encodeAddress } from "@polkadot/keyring";
 import BN from "bn.js";
 import Rmrk_factory from "../types/constructors/rmrk_example_equippable_lazy";
 import Rmrk from "../types/contracts/rmrk_example_equippable_lazy";
+import Catalog_factory from "../types/constructors/rmrk_example_catalog";
+import Catalog from "../types/contracts/rmrk_example_catalog";
 import {
   PartType,
   Part,
 } from "../types/types-arguments/rmrk_example_equippable_lazy";
+import { RmrkError } from "../types/types-returns/rmrk_example_equippable_lazy";
 
 import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
 import { KeyringPair } from "@polkadot/keyring/types";
@@ -33,12 +36,14 @@ const keyring = new Keyring({ type: "sr25519" });
 describe("RMRK Merged Equippable", () => {
   let kanariaFactory: Rmrk_factory;
   let gemFactory: Rmrk_factory;
+  let catalogFactory: Catalog_factory;
   let api: ApiPromise;
   let deployer: KeyringPair;
   let bob: KeyringPair;
   let dave: KeyringPair;
   let kanaria: Rmrk;
   let gem: Rmrk;
+  let catalog: Catalog;
 
   const ZERO_ADDRESS = encodeAddress(
     "0x0000000000000000000000000000000000000000000000000000000000000000"
@@ -85,6 +90,13 @@ describe("RMRK Merged Equippable", () => {
       deployer,
       api
     );
+
+    catalogFactory = new Catalog_factory(api, deployer);
+    catalog = new Catalog(
+      (await catalogFactory.new([BASE_METADATA])).address,
+      deployer,
+      api
+    );
   }
 
   it("Merged Equippable user journey", async () => {
@@ -96,13 +108,11 @@ describe("RMRK Merged Equippable", () => {
 
     const mintingKanariaCnt = 5;
 
-    // set Base metadata
+    // Base is a standalone catalog contract, referenced by kanaria assets
     console.log("Setting up Base");
-    const setupBaseGas = (await gem.query.setupBase([BASE_METADATA]))
-      .gasRequired;
-    await gem
-      .withSigner(deployer)
-      .tx.setupBase([BASE_METADATA], { gasLimit: setupBaseGas * 2n });
+    expect((await catalog.query.getBaseMetadata()).value).to.be.equal(
+      BASE_METADATA
+    );
     // define 2 test Parts
     const PART_LIST: Part[] = [
       // Background option 1
@@ -197,12 +207,12 @@ describe("RMRK Merged Equippable", () => {
 
     // add parts to base
     const addPartListGas = (
-      await gem.withSigner(deployer).query.addPartList(PART_LIST)
+      await catalog.withSigner(deployer).query.addPartList(PART_LIST)
     ).gasRequired;
-    await gem
+    await catalog
       .withSigner(deployer)
       .tx.addPartList(PART_LIST, { gasLimit: addPartListGas * 2n });
-    expect((await gem.query.getPartsCount())?.value).to.be.equal(11);
+    expect((await catalog.query.getPartsCount())?.value).to.be.equal(11);
     console.log("Base is set");
 
     // minting tokens
@@ -252,27 +262,72 @@ describe("RMRK Merged Equippable", () => {
         .query.addAssetEntry(
           assetDefaultId,
           "0",
+          null,
           ["ipfs://kanariaAsset1.png"],
           []
         )
     ).gasRequired;
     const addAssetResult = await kanaria
       .withSigner(deployer)
-      .tx.addAssetEntry(assetDefaultId, "0", ["ipfs://kanariaAsset1.png"], [], {
-        gasLimit: assetEntryGas * 2n,
-      });
+      .tx.addAssetEntry(
+        assetDefaultId,
+        "0",
+        null,
+        ["ipfs://kanariaAsset1.png"],
+        [],
+        { gasLimit: assetEntryGas * 2n }
+      );
     emit(addAssetResult, "AssetSet", { asset: 1 });
+
+    // composed asset parts are validated against the gem base
+    const failLocalParts = await kanaria
+      .withSigner(deployer)
+      .query.addAssetEntry(
+        assetComposedId,
+        "0",
+        null,
+        ["ipfs://kanariaAsset2.json"],
+        [0, 2, 4, 6, 8, 9, 10]
+      );
+    expect(failLocalParts.value.err.rmrk).to.be.equal(RmrkError.unknownPartId);
+    const failUnknownPart = await kanaria
+      .withSigner(deployer)
+      .query.addAssetEntry(
+        assetComposedId,
+        "0",
+        catalog.address,
+        ["ipfs://kanariaAsset2.json"],
+        [0, 42]
+      );
+    expect(failUnknownPart.value.err.rmrk).to.be.equal(
+      RmrkError.unknownPartId
+    );
+    const composedEntryGas = (
+      await kanaria
+        .withSigner(deployer)
+        .query.addAssetEntry(
+          assetComposedId,
+          "0",
+          catalog.address,
+          ["ipfs://kanariaAsset2.json"],
+          [0, 2, 4, 6, 8, 9, 10]
+        )
+    ).gasRequired;
     expect(
       await kanaria
         .withSigner(deployer)
         .tx.addAssetEntry(
           assetComposedId,
           "0",
+          catalog.address,
           ["ipfs://kanariaAsset2.json"],
           [0, 2, 4, 6, 8, 9, 10],
-          { gasLimit: assetEntryGas * 2n }
+          { gasLimit: composedEntryGas * 2n }
         )
     ).to.be.ok;
+    expect((await kanaria.query.getAssetBase(assetComposedId)).value).to.equal(
+      catalog.address
+    );
     // emit(addAssetResult, 'AssetSet', { asset: 2 });
     expect(
       (await kanaria.withSigner(deployer).query.totalAssets())?.value.toString()
@@ -332,11 +387,11 @@ describe("RMRK Merged Equippable", () => {
     const gemAssetAddGas = (
       await gem
         .withSigner(deployer)
-        .query.addAssetEntry(0, 0, ["ipfs://gems/typeA/full.svg"], [])
+        .query.addAssetEntry(0, 0, null, ["ipfs://gems/typeA/full.svg"], [])
     ).gasRequired;
     await gem
       .withSigner(deployer)
-      .tx.addAssetEntry(1, 0, ["ipfs://gems/typeA/full.svg"], [], {
+      .tx.addAssetEntry(1, 0, null, ["ipfs://gems/typeA/full.svg"], [], {
         gasLimit: gemAssetAddGas,
       });
     await gem
@@ -344,6 +399,7 @@ describe("RMRK Merged Equippable", () => {
       .tx.addAssetEntry(
         2,
         equippableRefIdLeftGem,
+        null,
         ["ipfs://gems/typeA/left.svg"],
         [],
         { gasLimit: gemAssetAddGas * 2n }
@@ -353,6 +409,7 @@ describe("RMRK Merged Equippable", () => {
       .tx.addAssetEntry(
         3,
         equippableRefIdMidGem,
+        null,
         ["ipfs://gems/typeA/mid.svg"],
         [],
         { gasLimit: gemAssetAddGas * 2n }
@@ -362,13 +419,14 @@ describe("RMRK Merged Equippable", () => {
       .tx.addAssetEntry(
         4,
         equippableRefIdRightGem,
+        null,
         ["ipfs://gems/typeA/right.svg"],
         [],
         { gasLimit: gemAssetAddGas * 2n }
       );
     await gem
       .withSigner(deployer)
-      .tx.addAssetEntry(5, 0, ["ipfs://gems/typeB/full.svg"], [], {
+      .tx.addAssetEntry(5, 0, null, ["ipfs://gems/typeB/full.svg"], [], {
         gasLimit: gemAssetAddGas * 2n,
       });
     await gem
@@ -376,6 +434,7 @@ describe("RMRK Merged Equippable", () => {
       .tx.addAssetEntry(
         6,
         equippableRefIdLeftGem,
+        null,
         ["ipfs://gems/typeB/left.svg"],
         [],
         { gasLimit: gemAssetAddGas * 2n }
@@ -385,6 +444,7 @@ describe("RMRK Merged Equippable", () => {
       .tx.addAssetEntry(
         7,
         equippableRefIdMidGem,
+        null,
         ["ipfs://gems/typeB/mid.svg"],
         [],
         { gasLimit: gemAssetAddGas * 2n }
@@ -394,6 +454,7 @@ describe("RMRK Merged Equippable", () => {
       .tx.addAssetEntry(
         8,
         equippableRefIdRightGem,
+        null,
         ["ipfs://gems/typeB/right.svg"],
         [],
         { gasLimit: gemAssetAddGas * 2n }
@@ -534,6 +595,19 @@ describe("RMRK Merged Equippable", () => {
 
     // Equipping
     console.log("Equipping gems to kanaria");
+    // slots are checked in the catalog, kanaria is not an equippable collection
+    const failNotEquippable = await kanaria
+      .withSigner(bob)
+      .query.equip(
+        { u64: 1 },
+        assetComposedId,
+        8,
+        [kanaria.address, { u64: 2 }],
+        2
+      );
+    expect(failNotEquippable.value.err.rmrk).to.be.equal(
+      RmrkError.addressNotEquippable
+    );
     const equipGas = (
       await kanaria
         .withSigner(bob)
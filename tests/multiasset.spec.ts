@@ -4,6 +4,7 @@ import { encodeAddress } from "@polkadot/keyring";
 import BN from "bn.js";
 import Rmrk_factory from "../types/constructors/rmrk_example_equippable_lazy";
 import Rmrk from "../types/contracts/rmrk_example_equippable_lazy";
+import { RmrkError } from "../types/types-returns/rmrk_example_equippable_lazy";
 
 import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
 import { KeyringPair } from "@polkadot/keyring/types";
@@ -164,21 +165,33 @@ describe("RMRK Multi Asset tests", () => {
     const assetEntryGas = (
       await kanaria
         .withSigner(deployer)
-        .query.addAssetEntry(assetDefaultId, "1", ["ipfs://default.png"], [0])
+        .query.addAssetEntry(
+          assetDefaultId,
+          "1",
+          null,
+          ["ipfs://default.png"],
+          []
+        )
     ).gasRequired;
     const addAssetResult = await kanaria
       .withSigner(deployer)
-      .tx.addAssetEntry(assetDefaultId, "1", ["ipfs://default.png"], [0], {
-        gasLimit: assetEntryGas * 2n,
-      });
+      .tx.addAssetEntry(
+        assetDefaultId,
+        "1",
+        null,
+        ["ipfs://default.png"],
+        [],
+        { gasLimit: assetEntryGas * 2n }
+      );
     emit(addAssetResult, "AssetSet", { asset: 1 });
     await kanaria
       .withSigner(deployer)
       .tx.addAssetEntry(
         assetComposedId,
         "1",
+        null,
         ["ipfs://meta1.json"],
-        [1, 3, 5, 7, 9, 10, 11],
+        [],
         { gasLimit: assetEntryGas * 2n }
       );
     expect(
@@ -235,11 +248,11 @@ describe("RMRK Multi Asset tests", () => {
     const gemAssetAddGas = (
       await gem
         .withSigner(deployer)
-        .query.addAssetEntry(0, 0, ["ipfs://gems/typeA/full.svg"], [0])
+        .query.addAssetEntry(0, 0, null, ["ipfs://gems/typeA/full.svg"], [])
     ).gasRequired;
     await gem
       .withSigner(deployer)
-      .tx.addAssetEntry(1, 0, ["ipfs://gems/typeA/full.svg"], [0], {
+      .tx.addAssetEntry(1, 0, null, ["ipfs://gems/typeA/full.svg"], [], {
         gasLimit: gemAssetAddGas,
       });
     await gem
@@ -247,8 +260,9 @@ describe("RMRK Multi Asset tests", () => {
       .tx.addAssetEntry(
         2,
         equippableRefIdLeftGem,
+        null,
         ["ipfs://gems/typeA/left.svg"],
-        [0],
+        [],
         { gasLimit: gemAssetAddGas * 2n }
       );
     await gem
@@ -256,8 +270,9 @@ describe("RMRK Multi Asset tests", () => {
       .tx.addAssetEntry(
         3,
         equippableRefIdMidGem,
+        null,
         ["ipfs://gems/typeA/mid.svg"],
-        [0],
+        [],
         { gasLimit: gemAssetAddGas * 2n }
       );
     await gem
@@ -265,13 +280,14 @@ describe("RMRK Multi Asset tests", () => {
       .tx.addAssetEntry(
         4,
         equippableRefIdRightGem,
+        null,
         ["ipfs://gems/typeA/right.svg"],
-        [0],
+        [],
         { gasLimit: gemAssetAddGas * 2n }
       );
     await gem
       .withSigner(deployer)
-      .tx.addAssetEntry(5, 0, ["ipfs://gems/typeB/full.svg"], [0], {
+      .tx.addAssetEntry(5, 0, null, ["ipfs://gems/typeB/full.svg"], [], {
         gasLimit: gemAssetAddGas * 2n,
       });
     await gem
@@ -279,8 +295,9 @@ describe("RMRK Multi Asset tests", () => {
       .tx.addAssetEntry(
         6,
         equippableRefIdLeftGem,
+        null,
         ["ipfs://gems/typeB/left.svg"],
-        [0],
+        [],
         { gasLimit: gemAssetAddGas * 2n }
       );
     await gem
@@ -288,8 +305,9 @@ describe("RMRK Multi Asset tests", () => {
       .tx.addAssetEntry(
         7,
         equippableRefIdMidGem,
+        null,
         ["ipfs://gems/typeB/mid.svg"],
-        [0],
+        [],
         { gasLimit: gemAssetAddGas * 2n }
       );
     await gem
@@ -297,8 +315,9 @@ describe("RMRK Multi Asset tests", () => {
       .tx.addAssetEntry(
         8,
         equippableRefIdRightGem,
+        null,
         ["ipfs://gems/typeB/right.svg"],
-        [0],
+        [],
         { gasLimit: gemAssetAddGas * 2n }
       );
     expect(
@@ -435,6 +454,291 @@ describe("RMRK Multi Asset tests", () => {
       ).to.be.equal("3,0");
     }
   });
+
+  it("Add asset entry with auto assigned id works", async () => {
+    await setup();
+
+    const autoGas = (
+      await kanaria
+        .withSigner(deployer)
+        .query.addAssetEntryAuto(0, null, ["ipfs://default.png"], [])
+    ).gasRequired;
+    expect(
+      (
+        await kanaria
+          .withSigner(deployer)
+          .query.addAssetEntryAuto(0, null, ["ipfs://default.png"], [])
+      ).value.ok
+    ).to.equal(1);
+    const autoResult = await kanaria
+      .withSigner(deployer)
+      .tx.addAssetEntryAuto(0, null, ["ipfs://default.png"], [], {
+        gasLimit: autoGas * 2n,
+      });
+    emit(autoResult, "AssetSet", { asset: 1 });
+
+    // imported asset id moves the counter past it
+    await kanaria
+      .withSigner(deployer)
+      .tx.addAssetEntry(10, 0, null, ["ipfs://imported.png"], [], {
+        gasLimit: autoGas * 2n,
+      });
+    const nextResult = await kanaria
+      .withSigner(deployer)
+      .tx.addAssetEntryAuto(0, null, ["ipfs://next.png"], [], {
+        gasLimit: autoGas * 2n,
+      });
+    emit(nextResult, "AssetSet", { asset: 11 });
+    expect(
+      (await kanaria.query.totalAssets())?.value.toString()
+    ).to.be.equal("3");
+  });
+
+  it("Pending assets limit and reject all assets work", async () => {
+    await setup();
+
+    // bob mints a kanaria token
+    const mintGas = (await kanaria.withSigner(bob).query.mint()).gasRequired;
+    await kanaria.withSigner(bob).tx.mint({
+      value: PRICE_PER_MINT,
+      gasLimit: mintGas * 2n,
+    });
+
+    // deployer adds three asset entries and lowers the pending limit to two
+    const assetEntryGas = (
+      await kanaria
+        .withSigner(deployer)
+        .query.addAssetEntry(1, 0, null, ["ipfs://asset.png"], [])
+    ).gasRequired;
+    for (let i = 1; i <= 3; i++) {
+      await kanaria
+        .withSigner(deployer)
+        .tx.addAssetEntry(i, 0, null, ["ipfs://asset.png"], [], {
+          gasLimit: assetEntryGas * 2n,
+        });
+    }
+    const setLimitGas = (
+      await kanaria.withSigner(deployer).query.setMaxPendingAssets(2)
+    ).gasRequired;
+    await kanaria
+      .withSigner(deployer)
+      .tx.setMaxPendingAssets(2, { gasLimit: setLimitGas * 2n });
+    expect((await kanaria.query.getMaxPendingAssets()).value).to.equal(2);
+
+    // deployer adds assets to bob's token until the limit is reached
+    const assetAddGas = (
+      await kanaria
+        .withSigner(deployer)
+        .query.addAssetToToken({ u64: 1 }, 1, null)
+    ).gasRequired;
+    for (let i = 1; i <= 2; i++) {
+      await kanaria
+        .withSigner(deployer)
+        .tx.addAssetToToken({ u64: 1 }, i, null, {
+          gasLimit: assetAddGas * 2n,
+        });
+    }
+    const failAddAsset = await kanaria
+      .withSigner(deployer)
+      .query.addAssetToToken({ u64: 1 }, 3, null);
+    expect(failAddAsset.value.err.rmrk).to.be.equal(
+      RmrkError.maxPendingAssetsReached
+    );
+
+    // only the token owner can reject all, and only as many assets as expected
+    const failRejectAll = await kanaria
+      .withSigner(deployer)
+      .query.rejectAllAssets({ u64: 1 }, 2);
+    expect(failRejectAll.value.err.rmrk).to.be.equal(RmrkError.notTokenOwner);
+    const failUnexpected = await kanaria
+      .withSigner(bob)
+      .query.rejectAllAssets({ u64: 1 }, 1);
+    expect(failUnexpected.value.err.rmrk).to.be.equal(
+      RmrkError.unexpectedNumberOfAssets
+    );
+    const rejectAllGas = (
+      await kanaria.withSigner(bob).query.rejectAllAssets({ u64: 1 }, 2)
+    ).gasRequired;
+    const rejectAllResult = await kanaria
+      .withSigner(bob)
+      .tx.rejectAllAssets({ u64: 1 }, 2, { gasLimit: rejectAllGas * 2n });
+    emit(rejectAllResult, "AssetRejected", {
+      token: { u64: 1 },
+      asset: 1,
+    });
+    expect(
+      (await kanaria.query.totalTokenAssets({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,0");
+  });
+
+  it("Asset approvals work", async () => {
+    await setup();
+
+    // bob mints a kanaria token and deployer adds a pending asset to it
+    const mintGas = (await kanaria.withSigner(bob).query.mint()).gasRequired;
+    await kanaria.withSigner(bob).tx.mint({
+      value: PRICE_PER_MINT,
+      gasLimit: mintGas * 2n,
+    });
+    const assetEntryGas = (
+      await kanaria
+        .withSigner(deployer)
+        .query.addAssetEntry(1, 0, null, ["ipfs://asset.png"], [])
+    ).gasRequired;
+    await kanaria
+      .withSigner(deployer)
+      .tx.addAssetEntry(1, 0, null, ["ipfs://asset.png"], [], {
+        gasLimit: assetEntryGas * 2n,
+      });
+    const assetAddGas = (
+      await kanaria
+        .withSigner(deployer)
+        .query.addAssetToToken({ u64: 1 }, 1, null)
+    ).gasRequired;
+    await kanaria
+      .withSigner(deployer)
+      .tx.addAssetToToken({ u64: 1 }, 1, null, { gasLimit: assetAddGas * 2n });
+
+    // dave can't accept the asset until bob approves him
+    const failAccept = await kanaria
+      .withSigner(dave)
+      .query.acceptAsset({ u64: 1 }, 1);
+    expect(failAccept.value.err.rmrk).to.be.equal(RmrkError.notTokenOwner);
+    const approveGas = (
+      await kanaria
+        .withSigner(bob)
+        .query.approveForAssets(dave.address, { u64: 1 })
+    ).gasRequired;
+    const approveResult = await kanaria
+      .withSigner(bob)
+      .tx.approveForAssets(dave.address, { u64: 1 }, {
+        gasLimit: approveGas * 2n,
+      });
+    emit(approveResult, "ApprovalForAssets", {
+      owner: bob.address,
+      operator: dave.address,
+      token: { u64: 1 },
+    });
+    expect(
+      (await kanaria.query.getApprovedForAssets({ u64: 1 })).value.ok
+    ).to.equal(dave.address);
+
+    const acceptGas = (
+      await kanaria.withSigner(dave).query.acceptAsset({ u64: 1 }, 1)
+    ).gasRequired;
+    const acceptResult = await kanaria
+      .withSigner(dave)
+      .tx.acceptAsset({ u64: 1 }, 1, { gasLimit: acceptGas * 2n });
+    emit(acceptResult, "AssetAccepted", {
+      token: { u64: 1 },
+      asset: 1,
+      replaces: null,
+    });
+
+    // asset approval doesn't allow token transfers
+    const failTransfer = await kanaria
+      .withSigner(dave)
+      .query.transfer(dave.address, { u64: 1 }, []);
+    expect(failTransfer.value.err).to.be.ok;
+
+    // approval for all assets
+    const approveAllGas = (
+      await kanaria
+        .withSigner(bob)
+        .query.setApprovalForAllForAssets(dave.address, true)
+    ).gasRequired;
+    const approveAllResult = await kanaria
+      .withSigner(bob)
+      .tx.setApprovalForAllForAssets(dave.address, true, {
+        gasLimit: approveAllGas * 2n,
+      });
+    emit(approveAllResult, "ApprovalForAllForAssets", {
+      owner: bob.address,
+      operator: dave.address,
+      approved: true,
+    });
+    expect(
+      (
+        await kanaria.query.isApprovedForAllForAssets(
+          bob.address,
+          dave.address
+        )
+      ).value
+    ).to.equal(true);
+  });
+  it("Asset approval is cleared when the token changes owner", async () => {
+    await setup();
+
+    // bob mints a kanaria token and approves dave for its assets
+    const mintGas = (await kanaria.withSigner(bob).query.mint()).gasRequired;
+    await kanaria.withSigner(bob).tx.mint({
+      value: PRICE_PER_MINT,
+      gasLimit: mintGas * 2n,
+    });
+    const approveGas = (
+      await kanaria
+        .withSigner(bob)
+        .query.approveForAssets(dave.address, { u64: 1 })
+    ).gasRequired;
+    await kanaria
+      .withSigner(bob)
+      .tx.approveForAssets(dave.address, { u64: 1 }, {
+        gasLimit: approveGas * 2n,
+      });
+    expect(
+      (await kanaria.query.getApprovedForAssets({ u64: 1 })).value.ok
+    ).to.equal(dave.address);
+
+    // bob transfers the token to deployer, who transfers it back
+    const transferGas = (
+      await kanaria
+        .withSigner(bob)
+        .query.transfer(deployer.address, { u64: 1 }, [])
+    ).gasRequired;
+    await kanaria
+      .withSigner(bob)
+      .tx.transfer(deployer.address, { u64: 1 }, [], {
+        gasLimit: transferGas * 2n,
+      });
+    expect(
+      (await kanaria.query.getApprovedForAssets({ u64: 1 })).value.ok
+    ).to.equal(null);
+    await kanaria
+      .withSigner(deployer)
+      .tx.transfer(bob.address, { u64: 1 }, [], {
+        gasLimit: transferGas * 2n,
+      });
+    expect((await kanaria.query.ownerOf({ u64: 1 })).value).to.equal(
+      bob.address
+    );
+
+    // the earlier approval doesn't come back
+    expect(
+      (await kanaria.query.getApprovedForAssets({ u64: 1 })).value.ok
+    ).to.equal(null);
+    const assetEntryGas = (
+      await kanaria
+        .withSigner(deployer)
+        .query.addAssetEntry(1, 0, null, ["ipfs://asset.png"], [])
+    ).gasRequired;
+    await kanaria
+      .withSigner(deployer)
+      .tx.addAssetEntry(1, 0, null, ["ipfs://asset.png"], [], {
+        gasLimit: assetEntryGas * 2n,
+      });
+    const assetAddGas = (
+      await kanaria
+        .withSigner(deployer)
+        .query.addAssetToToken({ u64: 1 }, 1, null)
+    ).gasRequired;
+    await kanaria
+      .withSigner(deployer)
+      .tx.addAssetToToken({ u64: 1 }, 1, null, { gasLimit: assetAddGas * 2n });
+    const failAccept = await kanaria
+      .withSigner(dave)
+      .query.acceptAsset({ u64: 1 }, 1);
+    expect(failAccept.value.err.rmrk).to.be.equal(RmrkError.notTokenOwner);
+  });
 });
 
 // Helper function to parse Events
@@ -5,6 +5,10 @@ import BN from "bn.js";
 import Rmrk_factory from "../types/constructors/rmrk_example_equippable_lazy";
 import Rmrk from "../types/contracts/rmrk_example_equippable_lazy";
 import { RmrkError } from "../types/types-returns/rmrk_example_equippable_lazy";
+import RevertingChild_factory from "../types/constructors/rmrk_example_reverting_child";
+import RevertingChild from "../types/contracts/rmrk_example_reverting_child";
+import ApprovingParent_factory from "../types/constructors/rmrk_example_approving_parent";
+import ApprovingParent from "../types/contracts/rmrk_example_approving_parent";
 
 import { ApiPromise, WsProvider, Keyring } from "@polkadot/api";
 import { KeyringPair } from "@polkadot/keyring/types";
@@ -236,6 +240,7 @@ describe("RMRK Nesting tests", () => {
       parent: { u64: 1 },
       childCollection: child.address,
       childTokenId: { u64: 1 },
+      to: bob.address,
     });
     expect(
       (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
@@ -346,6 +351,10 @@ describe("RMRK Nesting tests", () => {
     expect(
       (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
     ).to.be.equal("0,0");
+    // rejected child is returned to dave
+    expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(
+      dave.address
+    );
   });
 
   it("Add child (same user) works", async () => {
@@ -412,11 +421,6 @@ describe("RMRK Nesting tests", () => {
     expect(
       (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
     ).to.be.equal("1,0");
-    emit(addChildResult, "ChildAdded", {
-      to: { u64: 1 },
-      collection: child.address,
-      child: { u64: 1 },
-    });
 
     // since bob is owner of both parent and child it is automatically approved
     emit(addChildResult, "ChildAccepted", {
@@ -424,6 +428,7 @@ describe("RMRK Nesting tests", () => {
       collection: child.address,
       child: { u64: 1 },
     });
+    expect(childEvents(addChildResult)).to.eql(["ChildAccepted"]);
   });
 
   it("Add two parents, move/transfer child works", async () => {
@@ -495,8 +500,8 @@ describe("RMRK Nesting tests", () => {
       .tx.addChild({ u64: 2 }, [child.address, { u64: 1 }], {
         gasLimit: addChildGas,
       });
-    emit(addChildResult, "ChildAdded", {
-      to: { u64: 2 },
+    emit(addChildResult, "ChildAccepted", {
+      parent: { u64: 2 },
       collection: child.address,
       child: { u64: 1 },
     });
@@ -518,11 +523,14 @@ describe("RMRK Nesting tests", () => {
       .tx.transferChild({ u64: 2 }, { u64: 1 }, [child.address, { u64: 1 }], {
         gasLimit: transferChildGas,
       });
-    emit(transferChildResult, "ChildRemoved", {
-      parent: { u64: 2 },
+    emit(transferChildResult, "ChildTransferred", {
+      from: { u64: 2 },
+      to: { u64: 1 },
       childCollection: child.address,
       childTokenId: { u64: 1 },
+      pending: true,
     });
+    expect(childEvents(transferChildResult)).to.eql(["ChildTransferred"]);
     expect(
       (await parent.query.childrenBalance({ u64: 2 }))?.value.ok.toString()
     ).to.be.equal("0,0");
@@ -546,6 +554,7 @@ describe("RMRK Nesting tests", () => {
       collection: child.address,
       child: { u64: 1 },
     });
+    expect(childEvents(acceptChildResult)).to.eql(["ChildAccepted"]);
     expect(
       (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
     ).to.be.equal("1,0");
@@ -554,6 +563,15 @@ describe("RMRK Nesting tests", () => {
     expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(
       parent.address
     );
+    expect(
+      (await parent.query.isChildOf([child.address, { u64: 1 }])).value
+    ).to.equal(true);
+
+    // bob can't move the nested child token, he isn't approved by the parent contract
+    const failTransfer = await child
+      .withSigner(bob)
+      .query.transfer(bob.address, { u64: 1 }, []);
+    expect(failTransfer.value.err).to.not.be.undefined;
 
     // bob removes child
     const removeChildGas = (
@@ -570,13 +588,839 @@ describe("RMRK Nesting tests", () => {
       parent: { u64: 1 },
       childCollection: child.address,
       childTokenId: { u64: 1 },
+      to: bob.address,
     });
+    expect(childEvents(removeChildResult)).to.eql(["ChildRemoved"]);
     expect(
       (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
     ).to.be.equal("0,0");
 
     // bob owns child token (in child contract)
     expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(bob.address);
+    expect(
+      (await parent.query.isChildOf([child.address, { u64: 1 }])).value
+    ).to.equal(false);
+  });
+
+  it("Nested token can't be moved by an operator of its parent", async () => {
+    await setup();
+    const approvingFactory = new ApprovingParent_factory(api, deployer);
+    const approvingParent = new ApprovingParent(
+      (await approvingFactory.new()).address,
+      deployer,
+      api
+    );
+
+    // dave mints a child and transfers it to the parent contract
+    const mintGas = (await child.withSigner(dave).query.mint()).gasRequired;
+    await child
+      .withSigner(dave)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    const transferGas = (
+      await child
+        .withSigner(dave)
+        .query.transfer(approvingParent.address, { u64: 1 }, [])
+    ).gasRequired;
+    await child
+      .withSigner(dave)
+      .tx.transfer(approvingParent.address, { u64: 1 }, [], {
+        gasLimit: transferGas * 2n,
+      });
+
+    // the parent contract nests the child and approves bob for it
+    const setNestedGas = (
+      await approvingParent
+        .withSigner(deployer)
+        .query.setNested([child.address, { u64: 1 }], true)
+    ).gasRequired;
+    await approvingParent
+      .withSigner(deployer)
+      .tx.setNested([child.address, { u64: 1 }], true, {
+        gasLimit: setNestedGas * 2n,
+      });
+    const approveGas = (
+      await approvingParent
+        .withSigner(deployer)
+        .query.approveChild([child.address, { u64: 1 }], bob.address)
+    ).gasRequired;
+    await approvingParent
+      .withSigner(deployer)
+      .tx.approveChild([child.address, { u64: 1 }], bob.address, {
+        gasLimit: approveGas * 2n,
+      });
+    expect(
+      (
+        await child.query.allowance(approvingParent.address, bob.address, {
+          u64: 1,
+        })
+      ).value
+    ).to.equal(true);
+
+    // bob is approved, but can't move the token while it is nested
+    const failTransfer = await child
+      .withSigner(bob)
+      .query.transfer(bob.address, { u64: 1 }, []);
+    expect(hex2a(failTransfer.value.err.custom)).to.be.equal("ChildIsNested");
+
+    // once the child is removed from its parent, bob can move it
+    await approvingParent
+      .withSigner(deployer)
+      .tx.setNested([child.address, { u64: 1 }], false, {
+        gasLimit: setNestedGas * 2n,
+      });
+    const bobTransferGas = (
+      await child.withSigner(bob).query.transfer(bob.address, { u64: 1 }, [])
+    ).gasRequired;
+    const transferResult = await child
+      .withSigner(bob)
+      .tx.transfer(bob.address, { u64: 1 }, [], {
+        gasLimit: bobTransferGas * 2n,
+      });
+    emit(transferResult, "Transfer", {
+      from: approvingParent.address,
+      to: bob.address,
+      id: { u64: 1 },
+    });
+    expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(bob.address);
+  });
+
+  it("Remove child to other account works", async () => {
+    await setup();
+
+    // bob mints parent and child and nests the child
+    const mintGas = (await parent.withSigner(bob).query.mint()).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    await child
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    const approveGas = (
+      await child
+        .withSigner(bob)
+        .query.approve(parent.address, { u64: 1 }, true)
+    ).gasRequired;
+    await child
+      .withSigner(bob)
+      .tx.approve(parent.address, { u64: 1 }, true, { gasLimit: approveGas });
+    const addChildGas = (
+      await parent
+        .withSigner(bob)
+        .query.addChild({ u64: 1 }, [child.address, { u64: 1 }])
+    ).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.addChild({ u64: 1 }, [child.address, { u64: 1 }], {
+        gasLimit: addChildGas,
+      });
+
+    // dave fails to unnest bob's child to himself
+    const failRemove = await parent
+      .withSigner(dave)
+      .query.removeChildTo(
+        { u64: 1 },
+        [child.address, { u64: 1 }],
+        dave.address
+      );
+    expect(failRemove.value.err.rmrk).to.be.equal(RmrkError.notTokenOwner);
+
+    // bob unnests the child straight to dave
+    const removeChildGas = (
+      await parent
+        .withSigner(bob)
+        .query.removeChildTo(
+          { u64: 1 },
+          [child.address, { u64: 1 }],
+          dave.address
+        )
+    ).gasRequired;
+    const removeChildResult = await parent
+      .withSigner(bob)
+      .tx.removeChildTo({ u64: 1 }, [child.address, { u64: 1 }], dave.address, {
+        gasLimit: removeChildGas,
+      });
+    emit(removeChildResult, "ChildRemoved", {
+      parent: { u64: 1 },
+      childCollection: child.address,
+      childTokenId: { u64: 1 },
+      to: dave.address,
+    });
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,0");
+    expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(
+      dave.address
+    );
+  });
+
+  it("Send child to other contract's parent works", async () => {
+    await setup();
+    const destination = new Rmrk(
+      (
+        await parentFactory.new(
+          ["RmrkProject 3"],
+          ["RMKDEST"],
+          [BASE_URI],
+          MAX_SUPPLY,
+          PRICE_PER_MINT,
+          [COLLECTION_METADATA],
+          ZERO_ADDRESS,
+          0
+        )
+      ).address,
+      deployer,
+      api
+    );
+
+    // bob mints parent and child, dave mints destination parent
+    const mintGas = (await parent.withSigner(bob).query.mint()).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    await child
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    await destination
+      .withSigner(dave)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+
+    // bob nests child into parent
+    const approveGas = (
+      await child
+        .withSigner(bob)
+        .query.approve(parent.address, { u64: 1 }, true)
+    ).gasRequired;
+    await child
+      .withSigner(bob)
+      .tx.approve(parent.address, { u64: 1 }, true, { gasLimit: approveGas });
+    const addChildGas = (
+      await parent
+        .withSigner(bob)
+        .query.addChild({ u64: 1 }, [child.address, { u64: 1 }])
+    ).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.addChild({ u64: 1 }, [child.address, { u64: 1 }], {
+        gasLimit: addChildGas,
+      });
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("1,0");
+
+    // dave fails to send bob's child
+    const failSend = await parent
+      .withSigner(dave)
+      .query.sendChildTo(
+        { u64: 1 },
+        [child.address, { u64: 1 }],
+        destination.address,
+        { u64: 1 }
+      );
+    expect(failSend.value.err.rmrk).to.be.equal(RmrkError.notTokenOwner);
+
+    // bob sends child to dave's token on destination contract
+    const sendChildGas = (
+      await parent
+        .withSigner(bob)
+        .query.sendChildTo(
+          { u64: 1 },
+          [child.address, { u64: 1 }],
+          destination.address,
+          { u64: 1 }
+        )
+    ).gasRequired;
+    const sendChildResult = await parent
+      .withSigner(bob)
+      .tx.sendChildTo(
+        { u64: 1 },
+        [child.address, { u64: 1 }],
+        destination.address,
+        { u64: 1 },
+        { gasLimit: sendChildGas * 2n }
+      );
+    emit(sendChildResult, "ChildRemoved", {
+      parent: { u64: 1 },
+      childCollection: child.address,
+      childTokenId: { u64: 1 },
+      to: destination.address,
+    });
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,0");
+    expect(
+      (await destination.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,1");
+    expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(
+      destination.address
+    );
+  });
+
+  it("Child sent to other contract and rejected returns untracked", async () => {
+    await setup();
+    const destination = new Rmrk(
+      (
+        await parentFactory.new(
+          ["RmrkProject 3"],
+          ["RMKDEST"],
+          [BASE_URI],
+          MAX_SUPPLY,
+          PRICE_PER_MINT,
+          [COLLECTION_METADATA],
+          ZERO_ADDRESS,
+          0
+        )
+      ).address,
+      deployer,
+      api
+    );
+
+    // bob mints parent and child, dave mints destination parent
+    const mintGas = (await parent.withSigner(bob).query.mint()).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    await child
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    await destination
+      .withSigner(dave)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+
+    // bob nests child into parent and sends it to dave's token
+    const approveGas = (
+      await child
+        .withSigner(bob)
+        .query.approve(parent.address, { u64: 1 }, true)
+    ).gasRequired;
+    await child
+      .withSigner(bob)
+      .tx.approve(parent.address, { u64: 1 }, true, { gasLimit: approveGas });
+    const addChildGas = (
+      await parent
+        .withSigner(bob)
+        .query.addChild({ u64: 1 }, [child.address, { u64: 1 }])
+    ).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.addChild({ u64: 1 }, [child.address, { u64: 1 }], {
+        gasLimit: addChildGas,
+      });
+    const sendChildGas = (
+      await parent
+        .withSigner(bob)
+        .query.sendChildTo(
+          { u64: 1 },
+          [child.address, { u64: 1 }],
+          destination.address,
+          { u64: 1 }
+        )
+    ).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.sendChildTo(
+        { u64: 1 },
+        [child.address, { u64: 1 }],
+        destination.address,
+        { u64: 1 },
+        { gasLimit: sendChildGas * 2n }
+      );
+    expect(
+      (await destination.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,1");
+
+    // dave rejects the child, it returns to the sending contract
+    const rejectGas = (
+      await destination
+        .withSigner(dave)
+        .query.rejectChild({ u64: 1 }, [child.address, { u64: 1 }])
+    ).gasRequired;
+    const rejectResult = await destination
+      .withSigner(dave)
+      .tx.rejectChild({ u64: 1 }, [child.address, { u64: 1 }], {
+        gasLimit: rejectGas * 2n,
+      });
+    emit(rejectResult, "ChildRejected", {
+      parent: { u64: 1 },
+      childCollection: child.address,
+      childTokenId: { u64: 1 },
+    });
+    expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(
+      parent.address
+    );
+
+    // the original parent doesn't track the child anymore
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,0");
+    expect(
+      (await parent.query.isChildOf([child.address, { u64: 1 }])).value
+    ).to.equal(false);
+    const failRemove = await parent
+      .withSigner(bob)
+      .query.removeChild({ u64: 1 }, [child.address, { u64: 1 }]);
+    expect(failRemove.value.err).to.not.be.undefined;
+
+    // the admin recovers the child for bob
+    const reclaimGas = (
+      await parent
+        .withSigner(deployer)
+        .query.reclaimChild([child.address, { u64: 1 }], bob.address)
+    ).gasRequired;
+    await parent
+      .withSigner(deployer)
+      .tx.reclaimChild([child.address, { u64: 1 }], bob.address, {
+        gasLimit: reclaimGas * 2n,
+      });
+    expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(
+      bob.address
+    );
+  });
+
+  it("Children limits and reject all pending works", async () => {
+    await setup();
+
+    // bob mints parent, dave mints two children
+    const mintGas = (await parent.withSigner(bob).query.mint()).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    for (let i = 0; i < 2; i++) {
+      await child
+        .withSigner(dave)
+        .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    }
+
+    // only contributor can set limits
+    const failSetLimits = await parent
+      .withSigner(bob)
+      .query.setChildrenLimits(1, 1);
+    expect(failSetLimits.value.err).to.not.be.undefined;
+    const setLimitsGas = (
+      await parent.withSigner(deployer).query.setChildrenLimits(1, 1)
+    ).gasRequired;
+    await parent
+      .withSigner(deployer)
+      .tx.setChildrenLimits(1, 1, { gasLimit: setLimitsGas * 2n });
+    expect((await parent.query.getChildrenLimits()).value.toString()).to.equal(
+      "1,1"
+    );
+
+    // dave adds first child to bob's parent
+    for (let i = 1; i <= 2; i++) {
+      const approveGas = (
+        await child
+          .withSigner(dave)
+          .query.approve(parent.address, { u64: i }, true)
+      ).gasRequired;
+      await child
+        .withSigner(dave)
+        .tx.approve(parent.address, { u64: i }, true, { gasLimit: approveGas });
+    }
+    const addChildGas = (
+      await parent
+        .withSigner(dave)
+        .query.addChild({ u64: 1 }, [child.address, { u64: 1 }])
+    ).gasRequired;
+    await parent
+      .withSigner(dave)
+      .tx.addChild({ u64: 1 }, [child.address, { u64: 1 }], {
+        gasLimit: addChildGas,
+      });
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,1");
+
+    // second pending child exceeds the limit
+    const failAddChild = await parent
+      .withSigner(dave)
+      .query.addChild({ u64: 1 }, [child.address, { u64: 2 }]);
+    expect(failAddChild.value.err.rmrk).to.be.equal(
+      RmrkError.maxPendingChildrenReached
+    );
+
+    // only parent owner can reject all pending children
+    const failRejectAll = await parent
+      .withSigner(dave)
+      .query.rejectAllPending({ u64: 1 });
+    expect(failRejectAll.value.err.rmrk).to.be.equal(RmrkError.notTokenOwner);
+    const rejectAllGas = (
+      await parent.withSigner(bob).query.rejectAllPending({ u64: 1 })
+    ).gasRequired;
+    const rejectAllResult = await parent
+      .withSigner(bob)
+      .tx.rejectAllPending({ u64: 1 }, { gasLimit: rejectAllGas * 2n });
+    emit(rejectAllResult, "ChildRejected", {
+      parent: { u64: 1 },
+      childCollection: child.address,
+      childTokenId: { u64: 1 },
+    });
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,0");
+    expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(
+      dave.address
+    );
+  });
+
+  it("Reclaim untracked child works", async () => {
+    await setup();
+
+    // dave mints child and transfers it to parent contract without nesting it
+    const mintGas = (await child.withSigner(dave).query.mint()).gasRequired;
+    await child
+      .withSigner(dave)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    const transferGas = (
+      await child.withSigner(dave).query.transfer(parent.address, { u64: 1 }, [])
+    ).gasRequired;
+    await child
+      .withSigner(dave)
+      .tx.transfer(parent.address, { u64: 1 }, [], { gasLimit: transferGas });
+    expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(
+      parent.address
+    );
+
+    // only admin can reclaim the child
+    const failReclaim = await parent
+      .withSigner(dave)
+      .query.reclaimChild([child.address, { u64: 1 }], dave.address);
+    expect(failReclaim.value.err).to.not.be.undefined;
+
+    const reclaimGas = (
+      await parent
+        .withSigner(deployer)
+        .query.reclaimChild([child.address, { u64: 1 }], dave.address)
+    ).gasRequired;
+    await parent
+      .withSigner(deployer)
+      .tx.reclaimChild([child.address, { u64: 1 }], dave.address, {
+        gasLimit: reclaimGas * 2n,
+      });
+    expect((await child.query.ownerOf({ u64: 1 })).value).to.equal(
+      dave.address
+    );
+  });
+
+  it("Reject child works when the child reverts the return transfer", async () => {
+    await setup();
+    const revertingFactory = new RevertingChild_factory(api, deployer);
+    const reverting = new RevertingChild(
+      (await revertingFactory.new()).address,
+      deployer,
+      api
+    );
+
+    // bob mints parent, dave mints a child on the reverting collection
+    const mintGas = (await parent.withSigner(bob).query.mint()).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    const revertingMintGas = (
+      await reverting.withSigner(dave).query.mint(dave.address, { u64: 1 })
+    ).gasRequired;
+    await reverting
+      .withSigner(dave)
+      .tx.mint(dave.address, { u64: 1 }, { gasLimit: revertingMintGas * 2n });
+
+    // dave adds the child to bob's parent, it is pending
+    const approveGas = (
+      await reverting
+        .withSigner(dave)
+        .query.approve(parent.address, { u64: 1 }, true)
+    ).gasRequired;
+    await reverting
+      .withSigner(dave)
+      .tx.approve(parent.address, { u64: 1 }, true, { gasLimit: approveGas });
+    const addChildGas = (
+      await parent
+        .withSigner(dave)
+        .query.addChild({ u64: 1 }, [reverting.address, { u64: 1 }])
+    ).gasRequired;
+    await parent
+      .withSigner(dave)
+      .tx.addChild({ u64: 1 }, [reverting.address, { u64: 1 }], {
+        gasLimit: addChildGas * 2n,
+      });
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,1");
+
+    // the child collection starts reverting every transfer
+    const lockGas = (
+      await reverting.withSigner(deployer).query.setTransfersLocked(true)
+    ).gasRequired;
+    await reverting
+      .withSigner(deployer)
+      .tx.setTransfersLocked(true, { gasLimit: lockGas * 2n });
+
+    // bob still rejects the child, it stays with the parent contract
+    const rejectGas = (
+      await parent
+        .withSigner(bob)
+        .query.rejectChild({ u64: 1 }, [reverting.address, { u64: 1 }])
+    ).gasRequired;
+    const rejectResult = await parent
+      .withSigner(bob)
+      .tx.rejectChild({ u64: 1 }, [reverting.address, { u64: 1 }], {
+        gasLimit: rejectGas * 2n,
+      });
+    emit(rejectResult, "ChildRejected", {
+      parent: { u64: 1 },
+      childCollection: reverting.address,
+      childTokenId: { u64: 1 },
+    });
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,0");
+    expect(
+      (await parent.query.isChildOf([reverting.address, { u64: 1 }])).value
+    ).to.equal(false);
+    expect((await reverting.query.ownerOf({ u64: 1 })).value).to.equal(
+      parent.address
+    );
+
+    // once transfers work again the admin reclaims the child for dave
+    await reverting
+      .withSigner(deployer)
+      .tx.setTransfersLocked(false, { gasLimit: lockGas * 2n });
+    const reclaimGas = (
+      await parent
+        .withSigner(deployer)
+        .query.reclaimChild([reverting.address, { u64: 1 }], dave.address)
+    ).gasRequired;
+    await parent
+      .withSigner(deployer)
+      .tx.reclaimChild([reverting.address, { u64: 1 }], dave.address, {
+        gasLimit: reclaimGas * 2n,
+      });
+    expect((await reverting.query.ownerOf({ u64: 1 })).value).to.equal(
+      dave.address
+    );
+  });
+
+  it("Children storage cost doesn't grow with number of children", async () => {
+    await setup();
+    const CHILDREN = 8;
+
+    // bob mints parent, dave mints children
+    const mintGas = (await parent.withSigner(bob).query.mint()).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    const mintManyGas = (
+      await child
+        .withSigner(dave)
+        .query.mintMany(CHILDREN, { value: PRICE_PER_MINT.muln(CHILDREN) })
+    ).gasRequired;
+    await child.withSigner(dave).tx.mintMany(CHILDREN, {
+      value: PRICE_PER_MINT.muln(CHILDREN),
+      gasLimit: mintManyGas * 2n,
+    });
+
+    // dave adds all children to bob's parent and records the gas of each call
+    const addChildGas: bigint[] = [];
+    for (let i = 1; i <= CHILDREN; i++) {
+      const approveGas = (
+        await child
+          .withSigner(dave)
+          .query.approve(parent.address, { u64: i }, true)
+      ).gasRequired;
+      await child
+        .withSigner(dave)
+        .tx.approve(parent.address, { u64: i }, true, { gasLimit: approveGas });
+      const gas = (
+        await parent
+          .withSigner(dave)
+          .query.addChild({ u64: 1 }, [child.address, { u64: i }])
+      ).gasRequired;
+      addChildGas.push(gas);
+      await parent
+        .withSigner(dave)
+        .tx.addChild({ u64: 1 }, [child.address, { u64: i }], {
+          gasLimit: gas * 2n,
+        });
+    }
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal(`0,${CHILDREN}`);
+
+    // the first call initializes the count, later calls cost the same
+    const second = addChildGas[1];
+    const last = addChildGas[CHILDREN - 1];
+    console.log(`addChild gas: ${addChildGas.join(", ")}`);
+    expect(Number(last)).to.be.at.most(Number(second) * 1.05);
+
+    // rejecting the first child costs the same as rejecting the last one
+    const rejectFirstGas = (
+      await parent
+        .withSigner(bob)
+        .query.rejectChild({ u64: 1 }, [child.address, { u64: 1 }])
+    ).gasRequired;
+    const rejectLastGas = (
+      await parent
+        .withSigner(bob)
+        .query.rejectChild({ u64: 1 }, [child.address, { u64: CHILDREN }])
+    ).gasRequired;
+    console.log(`rejectChild gas: first ${rejectFirstGas}, last ${rejectLastGas}`);
+    expect(Number(rejectFirstGas)).to.be.at.most(Number(rejectLastGas) * 1.05);
+  });
+
+  it("Nesting within the same collection works", async () => {
+    await setup();
+
+    // bob mints bag token-1 and item token-2, dave mints item token-3
+    const mintGas = (await parent.withSigner(bob).query.mint()).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    await parent
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    await parent
+      .withSigner(dave)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+
+    // bob puts his item into his bag, no approval needed
+    const addChildGas = (
+      await parent
+        .withSigner(bob)
+        .query.addChild({ u64: 1 }, [parent.address, { u64: 2 }])
+    ).gasRequired;
+    const addChildResult = await parent
+      .withSigner(bob)
+      .tx.addChild({ u64: 1 }, [parent.address, { u64: 2 }], {
+        gasLimit: addChildGas * 2n,
+      });
+    emit(addChildResult, "Transfer", {
+      from: bob.address,
+      to: parent.address,
+      id: { u64: 2 },
+    });
+    expect(childEvents(addChildResult)).to.eql(["ChildAccepted"]);
+    expect((await parent.query.ownerOf({ u64: 2 })).value).to.equal(
+      parent.address
+    );
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("1,0");
+
+    // dave adds his item to bob's bag, bob rejects it and dave gets it back
+    const davesAddGas = (
+      await parent
+        .withSigner(dave)
+        .query.addChild({ u64: 1 }, [parent.address, { u64: 3 }])
+    ).gasRequired;
+    await parent
+      .withSigner(dave)
+      .tx.addChild({ u64: 1 }, [parent.address, { u64: 3 }], {
+        gasLimit: davesAddGas * 2n,
+      });
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("1,1");
+    const rejectGas = (
+      await parent
+        .withSigner(bob)
+        .query.rejectChild({ u64: 1 }, [parent.address, { u64: 3 }])
+    ).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.rejectChild({ u64: 1 }, [parent.address, { u64: 3 }], {
+        gasLimit: rejectGas * 2n,
+      });
+    expect((await parent.query.ownerOf({ u64: 3 })).value).to.equal(
+      dave.address
+    );
+
+    // dave can't move bob's nested item into his own token
+    const failReparent = await parent
+      .withSigner(dave)
+      .query.addChild({ u64: 3 }, [parent.address, { u64: 2 }]);
+    expect(failReparent.value.err.rmrk).to.be.equal(RmrkError.childIsNested);
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("1,0");
+    expect(
+      (await parent.query.childrenBalance({ u64: 3 }))?.value.ok.toString()
+    ).to.be.equal("0,0");
+
+    // bob can't put the bag into the item it holds
+    const failAddChild = await parent
+      .withSigner(bob)
+      .query.addChild({ u64: 2 }, [parent.address, { u64: 1 }]);
+    expect(failAddChild.value.err.rmrk).to.be.equal(RmrkError.nestingCycle);
+
+    // bob takes the item out of the bag
+    const removeChildGas = (
+      await parent
+        .withSigner(bob)
+        .query.removeChild({ u64: 1 }, [parent.address, { u64: 2 }])
+    ).gasRequired;
+    const removeChildResult = await parent
+      .withSigner(bob)
+      .tx.removeChild({ u64: 1 }, [parent.address, { u64: 2 }], {
+        gasLimit: removeChildGas * 2n,
+      });
+    emit(removeChildResult, "ChildRemoved", {
+      parent: { u64: 1 },
+      childCollection: parent.address,
+      childTokenId: { u64: 2 },
+      to: bob.address,
+    });
+    expect((await parent.query.ownerOf({ u64: 2 })).value).to.equal(
+      bob.address
+    );
+    expect(
+      (await parent.query.childrenBalance({ u64: 1 }))?.value.ok.toString()
+    ).to.be.equal("0,0");
+  });
+
+  it("Nesting cycle is rejected", async () => {
+    await setup();
+
+    // bob mints parent and child
+    const mintGas = (await parent.withSigner(bob).query.mint()).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+    await child
+      .withSigner(bob)
+      .tx.mint({ value: PRICE_PER_MINT, gasLimit: mintGas * 2n });
+
+    // bob nests child token into parent token
+    const approveGas = (
+      await child
+        .withSigner(bob)
+        .query.approve(parent.address, { u64: 1 }, true)
+    ).gasRequired;
+    await child
+      .withSigner(bob)
+      .tx.approve(parent.address, { u64: 1 }, true, { gasLimit: approveGas });
+    const addChildGas = (
+      await parent
+        .withSigner(bob)
+        .query.addChild({ u64: 1 }, [child.address, { u64: 1 }])
+    ).gasRequired;
+    await parent
+      .withSigner(bob)
+      .tx.addChild({ u64: 1 }, [child.address, { u64: 1 }], {
+        gasLimit: addChildGas,
+      });
+    expect(
+      (await parent.query.getParentOfChild([child.address, { u64: 1 }])).value
+    ).to.not.equal(null);
+
+    // bob fails to nest parent token into its own child
+    const failAddChild = await child
+      .withSigner(bob)
+      .query.addChild({ u64: 1 }, [parent.address, { u64: 1 }]);
+    expect(failAddChild.value.err.rmrk).to.be.equal(RmrkError.nestingCycle);
+
+    // a token can't be nested into itself
+    const failAddSelf = await parent
+      .withSigner(bob)
+      .query.addChild({ u64: 1 }, [parent.address, { u64: 1 }]);
+    expect(failAddSelf.value.err.rmrk).to.be.equal(RmrkError.nestingCycle);
   });
 });
 
@@ -594,6 +1438,14 @@ function emit(result: { events?: any }, name: string, args: any): void {
   expect(event).eql({ name, args });
 }
 
+// Helper function to list names of nesting events, in emission order
+// eslint-disable-next-line @typescript-eslint/no-explicit-any
+function childEvents(result: { events?: any }): string[] {
+  return result.events
+    .map((event: { name: string }) => event.name)
+    .filter((name: string) => name.startsWith("Child"));
+}
+
 // Helper function to convert error code to string
 function hex2a(psp34CustomError: any): string {
   var hex = psp34CustomError.toString(); //force conversion